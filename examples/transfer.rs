@@ -0,0 +1,24 @@
+use adrive_api_rs::{ADriveAPI, Auth, FileId, Result};
+
+// cargo run --bin sign -- ./other-credentials
+#[tokio::main]
+async fn main() -> Result<()> {
+    let source = ADriveAPI::new();
+    let target = ADriveAPI::with_auth(Auth::with_credentials("./other-credentials"));
+    let drive_id = source.get_backup_drive_id().await?;
+    let target_drive_id = target.get_backup_drive_id().await?;
+
+    let file_id = FileId::from("62e89bd3b9d6ab9196c949a8b6a0f63a4dc22857");
+    let resp = source
+        .rapid_transfer(
+            &drive_id,
+            &file_id,
+            &target,
+            &target_drive_id,
+            &FileId::ROOT,
+            None,
+        )
+        .await?;
+    println!("{:#?}", resp);
+    Ok(())
+}
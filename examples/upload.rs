@@ -1,13 +1,24 @@
-use adrive_api_rs::ADriveAPI;
 use adrive_api_rs::Result;
+use adrive_api_rs::{ADriveAPI, FileId, ProgressEvent, UploadOptions};
+use std::sync::Arc;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let api = ADriveAPI::new();
     let drive_id = api.get_backup_drive_id().await?;
 
-    let parent_id: &str = "root";
+    let parent_id = FileId::ROOT;
     let file_path = "/path/to/file";
-    api.upload_file(&drive_id, parent_id, file_path).await?;
+    let options = UploadOptions {
+        progress: Some(Arc::new(|event: &ProgressEvent| {
+            println!(
+                "{} {:?} {}/{:?}",
+                event.file_name, event.phase, event.transferred_bytes, event.total_bytes
+            )
+        })),
+        ..Default::default()
+    };
+    api.upload_file(&drive_id, &parent_id, file_path, Some(&options))
+        .await?;
     Ok(())
 }
@@ -0,0 +1,23 @@
+use adrive_api_rs::Result;
+use adrive_api_rs::{ADriveAPI, FileId};
+
+// pg_dump mydb | zstd | cargo run --example upload_stream
+#[tokio::main]
+async fn main() -> Result<()> {
+    let api = ADriveAPI::new();
+    let drive_id = api.get_backup_drive_id().await?;
+
+    let parent_id = FileId::ROOT;
+    let resp = api
+        .upload_stream(
+            &drive_id,
+            &parent_id,
+            "mydb.sql.zst",
+            tokio::io::stdin(),
+            None,
+            None,
+        )
+        .await?;
+    println!("{:#?}", resp);
+    Ok(())
+}
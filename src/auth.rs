@@ -8,17 +8,29 @@ use tracing::info;
 
 use chrono::Utc;
 
-pub struct Auth {}
+#[derive(Default)]
+pub struct Auth {
+    credentials: Option<PathBuf>,
+}
 
 impl Auth {
-    fn path() -> PathBuf {
-        dirs::config_dir()
-            .expect("no config dir detected")
-            .join("adrive-api-rs/credentials")
+    // keeps the token of another account apart from the default credentials
+    pub fn with_credentials(path: impl Into<PathBuf>) -> Self {
+        Self {
+            credentials: Some(path.into()),
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        self.credentials.clone().unwrap_or_else(|| {
+            dirs::config_dir()
+                .expect("no config dir detected")
+                .join("adrive-api-rs/credentials")
+        })
     }
 
     fn dump(&self, token: &GetAccessTokenResponse) -> crate::Result<()> {
-        let path = &Self::path();
+        let path = &self.path();
         if !path.exists() {
             fs::create_dir_all(path.parent().expect("no parent dir detected"))?;
             fs::File::create(path)?;
@@ -29,8 +41,8 @@ impl Auth {
         Ok(())
     }
 
-    fn load() -> crate::Result<GetAccessTokenResponse> {
-        let file = fs::File::open(Self::path())?;
+    fn load(&self) -> crate::Result<GetAccessTokenResponse> {
+        let file = fs::File::open(self.path())?;
         let token: GetAccessTokenResponse = serde_json::from_reader(file)?;
         Ok(token)
     }
@@ -53,6 +65,7 @@ impl Auth {
                     info!("⛔️ 二维码已过期");
                     break None;
                 }
+                QRCodeStatus::Unknown(status) => info!("❔ 未知状态 {}，继续等待...", status),
             }
             thread::sleep(time::Duration::from_secs(1))
         };
@@ -71,7 +84,7 @@ impl Auth {
     }
 
     async fn refresh_token(&self) -> crate::Result<GetAccessTokenResponse> {
-        let token = Self::load()?;
+        let token = self.load()?;
         let resp = GetAccessTokenRequest3 {
             refresh_token: token.refresh_token,
         }
@@ -82,7 +95,7 @@ impl Auth {
     }
 
     pub async fn refresh_if_needed(&self) -> crate::Result<GetAccessTokenResponse> {
-        let token = Self::load()?;
+        let token = self.load()?;
         if Utc::now().timestamp() - token.time.timestamp() >= token.expires_in {
             let token = self.refresh_token().await?;
             Ok(token)
@@ -1,10 +1,13 @@
 use adrive_api_rs::Auth;
-use tracing_subscriber;
 
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt::init();
 
-    let auth = Auth {};
+    // an optional path signs another account in without touching the default credentials
+    let auth = match std::env::args().nth(1) {
+        Some(path) => Auth::with_credentials(path),
+        None => Auth::default(),
+    };
     auth.sign_in().await.unwrap();
 }
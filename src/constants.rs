@@ -3,4 +3,13 @@ pub(crate) const SELF_HOSTING_SERVER: &str = "https://adrive-sign-in.hipster.wor
 
 pub(crate) const MAX_BATCH_SIZE: usize = 100;
 pub(crate) const MAX_CONCURRENCY: usize = 10;
+pub(crate) const ASYNC_TASK_POLL_MILLIS: u64 = 500;
+pub(crate) const ASYNC_TASK_MAX_POLL_MILLIS: u64 = 10_000;
+pub(crate) const ASYNC_TASK_TIMEOUT_SECS: u64 = 30 * 60;
 pub(crate) const CHUNK_SIZE: u64 = 64 * 1024 * 1024;
+pub(crate) const MAX_PART_COUNT: u64 = 10_000;
+pub(crate) const MIN_PART_SIZE: u64 = 100 * 1024;
+pub(crate) const MAX_PART_SIZE: u64 = 5 * 1024 * 1024 * 1024;
+pub(crate) const STREAM_PARTS_PER_STEP: u64 = 1_000;
+// each part of a stream is held in memory while it's sent
+pub(crate) const STREAM_MAX_PART_SIZE: u64 = 512 * 1024 * 1024;
@@ -1,22 +1,33 @@
 use crate::data::{
-    AsyncTaskResponse, BatchGetFilesRequest, CompleteUploadRequest, CopyFileRequest,
-    CreateFileRequest, CreateFileResponse, DeleteFileRequest, DownloadFileRequest, FileEntry,
-    FileType, FlushUploadUrlRequest, FlushUploadUrlResponse, GetAccessTokenResponse,
+    AsyncTaskResponse, BatchGetFilesRequest, ClearRecycleBinRequest, ClearRecycleBinResponse,
+    CompleteUploadRequest, CopyFileRequest, CreateFileRequest, CreateFileResponse,
+    DeleteFileRequest, DownloadFileRequest, DriveId, FileEntry, FileId, FileType,
+    FlushUploadUrlRequest, FlushUploadUrlResponse, GetAccessTokenResponse,
     GetAsyncTaskStateRequest, GetAsyncTaskStateResponse, GetDownloadUrlRequest,
     GetDownloadUrlResponse, GetDriveInfoRequest, GetDriveInfoResponse, GetFileByIdRequest,
     GetFileByPathRequest, GetSpaceInfoRequest, GetSpaceInfoResponse, GetUserInfoRequest,
-    GetUserInfoResponse, IfNameExists, ListFilesRequest, ListFilesResponse,
-    ListStarredFilesRequest, ListUploadedPartsRequest, ListUploadedPartsResponse, MoveFileRequest,
-    OrderBy, PartInfo, RecycleFileRequest, Request, SearchFilesRequest, SortBy, UpdateFileRequest,
+    GetUserInfoResponse, GetVideoPreviewPlayInfoRequest, GetVideoPreviewPlayInfoResponse,
+    IfNameExists, ListFilesRequest, ListFilesResponse, ListRecentVideosRequest,
+    ListRecycleBinRequest, ListStarredFilesRequest, ListUploadedPartsRequest,
+    ListUploadedPartsResponse, MoveFileRequest, OrderBy, PartInfo, RecycleFileRequest, Request,
+    RestoreFileRequest, SearchFilesRequest, SortBy, TaskId, ThumbnailOptions, UpdateFileRequest,
+    UpdateVideoRecordRequest, UpdateVideoRecordResponse, UploadId, UploadedParts,
 };
+use crate::progress::{Phase, ProgressTracker};
+use crate::throttle::{self, BandwidthLimit};
+use crate::transfer::{UploadKind, UploadOptions, UploadOutcome};
 use crate::{auth, constants, utils};
 
 use anyhow::{anyhow, ensure};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
 use reqwest::header::HeaderMap;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::os::unix::fs::MetadataExt;
 use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 use std::{fs, io::Write};
+use tokio::io::{AsyncRead, AsyncReadExt};
 
 pub type Result<T> = anyhow::Result<T>;
 
@@ -24,6 +35,8 @@ pub static TOKIO_RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
 
 pub struct ADriveCoreAPI {
     auth: auth::Auth,
+    upload_limit: BandwidthLimit,
+    download_limit: BandwidthLimit,
 }
 
 impl Default for ADriveCoreAPI {
@@ -34,11 +47,33 @@ impl Default for ADriveCoreAPI {
 
 impl ADriveCoreAPI {
     pub fn new() -> Self {
+        Self::with_auth(auth::Auth::default())
+    }
+
+    pub fn with_auth(auth: auth::Auth) -> Self {
         Self {
-            auth: auth::Auth {},
+            auth,
+            upload_limit: BandwidthLimit::default(),
+            download_limit: BandwidthLimit::default(),
         }
     }
 
+    // the caps shared by every transfer of this client
+    pub fn upload_limit(&self) -> &BandwidthLimit {
+        &self.upload_limit
+    }
+
+    pub fn download_limit(&self) -> &BandwidthLimit {
+        &self.download_limit
+    }
+
+    pub(crate) fn limits(
+        global: &BandwidthLimit,
+        limit: Option<&BandwidthLimit>,
+    ) -> Vec<BandwidthLimit> {
+        std::iter::once(global).chain(limit).cloned().collect()
+    }
+
     pub async fn get_token(&self) -> Result<GetAccessTokenResponse> {
         self.auth.refresh_if_needed().await
     }
@@ -68,9 +103,10 @@ impl ADriveCoreAPI {
 
     pub async fn list_files(
         &self,
-        drive_id: &str,
-        parent_file_id: &str,
+        drive_id: &DriveId,
+        parent_file_id: &FileId,
         marker: Option<&str>,
+        thumbnail: Option<&ThumbnailOptions>,
     ) -> Result<ListFilesResponse> {
         let token = self.auth.refresh_if_needed().await?;
         let resp = ListFilesRequest::new(
@@ -81,6 +117,7 @@ impl ADriveCoreAPI {
             Some(SortBy::Asc),
             None,
             None,
+            thumbnail,
         )
         .dispatch(None, Some(&token.access_token))
         .await?;
@@ -89,13 +126,14 @@ impl ADriveCoreAPI {
 
     pub async fn search_files(
         &self,
-        drive_id: &str,
+        drive_id: &DriveId,
         query: &str,
         marker: Option<&str>,
         order_by: Option<&str>,
+        thumbnail: Option<&ThumbnailOptions>,
     ) -> Result<ListFilesResponse> {
         let token = self.auth.refresh_if_needed().await?;
-        let resp = SearchFilesRequest::new(drive_id, Some(query), marker, order_by)
+        let resp = SearchFilesRequest::new(drive_id, Some(query), marker, order_by, thumbnail)
             .dispatch(None, Some(&token.access_token))
             .await?;
         Ok(resp)
@@ -103,25 +141,31 @@ impl ADriveCoreAPI {
 
     pub async fn list_starred_files(
         &self,
-        drive_id: &str,
+        drive_id: &DriveId,
         marker: Option<&str>,
+        thumbnail: Option<&ThumbnailOptions>,
     ) -> Result<ListFilesResponse> {
         let token = self.auth.refresh_if_needed().await?;
-        let resp = ListStarredFilesRequest::new(drive_id, marker)
+        let resp = ListStarredFilesRequest::new(drive_id, marker, thumbnail)
             .dispatch(None, Some(&token.access_token))
             .await?;
         Ok(resp)
     }
 
-    pub async fn get_file_by_id(&self, drive_id: &str, file_id: &str) -> Result<FileEntry> {
+    pub async fn get_file_by_id(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        thumbnail: Option<&ThumbnailOptions>,
+    ) -> Result<FileEntry> {
         let token = self.auth.refresh_if_needed().await?;
-        let resp = GetFileByIdRequest::new(drive_id, file_id)
+        let resp = GetFileByIdRequest::new(drive_id, file_id, thumbnail)
             .dispatch(None, Some(&token.access_token))
             .await?;
         Ok(resp)
     }
 
-    pub async fn get_file_by_path(&self, drive_id: &str, file_path: &str) -> Result<FileEntry> {
+    pub async fn get_file_by_path(&self, drive_id: &DriveId, file_path: &str) -> Result<FileEntry> {
         let token = self.auth.refresh_if_needed().await?;
         let resp = GetFileByPathRequest::new(drive_id, file_path)
             .dispatch(None, Some(&token.access_token))
@@ -131,22 +175,23 @@ impl ADriveCoreAPI {
 
     pub async fn batch_get_files(
         &self,
-        drive_id: &str,
-        file_ids: &[&str],
+        drive_id: &DriveId,
+        file_ids: &[FileId],
+        thumbnail: Option<&ThumbnailOptions>,
     ) -> Result<ListFilesResponse> {
         if file_ids.len() > constants::MAX_BATCH_SIZE {
             return Err(anyhow!("the max batch size should not exceed 100"));
         }
         let token = self.auth.refresh_if_needed().await?;
-        BatchGetFilesRequest::new(drive_id, file_ids)
+        BatchGetFilesRequest::new(drive_id, file_ids, thumbnail)
             .dispatch(None, Some(&token.access_token))
             .await
     }
 
     pub async fn get_download_url(
         &self,
-        drive_id: &str,
-        file_id: &str,
+        drive_id: &DriveId,
+        file_id: &FileId,
     ) -> Result<GetDownloadUrlResponse> {
         let token = self.auth.refresh_if_needed().await?;
         GetDownloadUrlRequest::new(drive_id, file_id)
@@ -156,12 +201,13 @@ impl ADriveCoreAPI {
 
     pub async fn download_file(
         &self,
-        drive_id: &str,
-        file_id: &str,
+        drive_id: &DriveId,
+        file_id: &FileId,
         file_handle: &mut fs::File,
         download_url: Option<&str>,
         start: Option<&str>,
         end: Option<&str>,
+        limit: Option<&BandwidthLimit>,
     ) -> Result<()> {
         let token = self.auth.refresh_if_needed().await?;
         let url = if let Some(url) = download_url {
@@ -181,20 +227,48 @@ impl ADriveCoreAPI {
                 .parse()?,
             );
         }
-        let bytes = DownloadFileRequest { url: &url }
+        let resp = DownloadFileRequest { url: &url }
             .get_original(Some(headers), Some(&token.access_token))
-            .await?
-            .bytes()
             .await?;
+        let bytes = throttle::read_body(resp, &Self::limits(&self.download_limit, limit)).await?;
         Ok(file_handle.write_all(&bytes)?)
     }
 
+    // fetches a signed url like a thumbnail as a whole, the url is all the
+    // authorization it needs so the access token isn't sent along
+    pub async fn download_url(&self, url: &str) -> Result<Bytes> {
+        let resp = DownloadFileRequest { url }
+            .get_original(None, None)
+            .await?
+            .error_for_status()?;
+        throttle::read_body(resp, &Self::limits(&self.download_limit, None)).await
+    }
+
+    // end is inclusive, like the Range header
+    pub async fn download_range(
+        &self,
+        download_url: &str,
+        start: u64,
+        end: u64,
+        limit: Option<&BandwidthLimit>,
+    ) -> Result<Bytes> {
+        let token = self.auth.refresh_if_needed().await?;
+        let mut headers = HeaderMap::new();
+        headers.append("Range", format!("bytes={}-{}", start, end).parse()?);
+        let resp = DownloadFileRequest { url: download_url }
+            .get_original(Some(headers), Some(&token.access_token))
+            .await?
+            .error_for_status()?;
+        throttle::read_body(resp, &Self::limits(&self.download_limit, limit)).await
+    }
+
     pub async fn download_file2(
         file_handle: Arc<Mutex<fs::File>>,
         download_url: String,
         token: String,
         start: Option<String>,
         end: Option<String>,
+        limits: Vec<BandwidthLimit>,
     ) {
         let mut headers = HeaderMap::new();
         if start.is_some() || end.is_some() {
@@ -209,13 +283,11 @@ impl ADriveCoreAPI {
                 .unwrap(),
             );
         }
-        let bytes = DownloadFileRequest { url: &download_url }
+        let resp = DownloadFileRequest { url: &download_url }
             .get_original(Some(headers), Some(&token))
             .await
-            .unwrap()
-            .bytes()
-            .await
             .unwrap();
+        let bytes = throttle::read_body(resp, &limits).await.unwrap();
         let mut file_handle = file_handle.lock().unwrap();
         let start = start.map_or(0, |v| v.parse::<u64>().unwrap());
         file_handle.seek(SeekFrom::Start(start)).unwrap();
@@ -225,9 +297,10 @@ impl ADriveCoreAPI {
     // 只能创建单层文件夹，dirname 不能是 a/b/c 这种形式
     pub async fn create_folder(
         &self,
-        drive_id: &str,
-        parent_file_id: &str,
+        drive_id: &DriveId,
+        parent_file_id: &FileId,
         dir_name: &str,
+        check_name_mode: Option<IfNameExists>,
     ) -> Result<CreateFileResponse> {
         let token = &self.auth.refresh_if_needed().await?;
         CreateFileRequest::new(
@@ -235,6 +308,9 @@ impl ADriveCoreAPI {
             parent_file_id,
             dir_name,
             FileType::Folder,
+            check_name_mode,
+            None,
+            None,
             None,
             None,
             None,
@@ -249,17 +325,21 @@ impl ADriveCoreAPI {
 
     pub async fn create_multipart_upload(
         &self,
-        drive_id: &str,
-        parent_file_id: &str,
+        drive_id: &DriveId,
+        parent_file_id: &FileId,
         file_name: &str,
         part_info_list: Option<Vec<PartInfo>>,
+        options: &UploadOptions,
     ) -> Result<CreateFileResponse> {
         let token = &self.auth.refresh_if_needed().await?;
+        let local_created_at = options.local_created_at.as_ref().map(utils::format_time);
+        let local_modified_at = options.local_modified_at.as_ref().map(utils::format_time);
         CreateFileRequest::new(
             drive_id,
             parent_file_id,
             file_name,
             FileType::File,
+            Some(options.if_name_exists),
             part_info_list,
             None,
             None,
@@ -267,6 +347,8 @@ impl ADriveCoreAPI {
             None,
             None,
             None,
+            local_created_at.as_deref(),
+            local_modified_at.as_deref(),
         )
         .dispatch(None, Some(&token.access_token))
         .await
@@ -274,9 +356,9 @@ impl ADriveCoreAPI {
 
     pub async fn flush_upload_url(
         &self,
-        drive_id: &str,
-        file_id: &str,
-        upload_id: &str,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        upload_id: &UploadId,
         part_number_list: &[u16],
     ) -> Result<FlushUploadUrlResponse> {
         let token = &self.auth.refresh_if_needed().await?;
@@ -287,9 +369,9 @@ impl ADriveCoreAPI {
 
     pub async fn list_multipart_uploads(
         &self,
-        drive_id: &str,
-        file_id: &str,
-        upload_id: &str,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        upload_id: &UploadId,
         marker: Option<String>,
     ) -> Result<ListUploadedPartsResponse> {
         let token = &self.auth.refresh_if_needed().await?;
@@ -300,9 +382,9 @@ impl ADriveCoreAPI {
 
     pub async fn complete_multipart_upload(
         &self,
-        drive_id: &str,
-        file_id: &str,
-        upload_id: &str,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        upload_id: &UploadId,
     ) -> Result<FileEntry> {
         let token = &self.auth.refresh_if_needed().await?;
         CompleteUploadRequest::new(drive_id, file_id, upload_id)
@@ -310,18 +392,98 @@ impl ADriveCoreAPI {
             .await
     }
 
-    pub async fn upload_part(&self, part_info: &PartInfo, buffer: Vec<u8>) -> Result<()> {
+    pub async fn upload_part(
+        &self,
+        part_info: &PartInfo,
+        buffer: Vec<u8>,
+        limit: Option<&BandwidthLimit>,
+    ) -> Result<()> {
         let token = &self.auth.refresh_if_needed().await?;
+        let limits = Self::limits(&self.upload_limit, limit);
         part_info
-            .put_original(None, Some(&token.access_token), buffer)
+            .put_original(None, Some(&token.access_token), buffer, limits)
             .await?;
         Ok(())
     }
 
     pub const PART_SIZE: u64 = 64 * 1024 * 1024; // 64MB
 
-    pub fn create_part_info_list(size: u64) -> Result<Vec<PartInfo>> {
-        let count = (size + Self::PART_SIZE - 1) / Self::PART_SIZE;
+    // PART_SIZE unless the file needs more than MAX_PART_COUNT parts, then the
+    // smallest MB aligned size which fits
+    pub fn part_size_for(file_size: u64, part_size: Option<u64>) -> Result<u64> {
+        let part_size = part_size.unwrap_or_else(|| {
+            file_size
+                .div_ceil(constants::MAX_PART_COUNT)
+                .next_multiple_of(1024 * 1024)
+                .max(Self::PART_SIZE)
+        });
+        ensure!(
+            (constants::MIN_PART_SIZE..=constants::MAX_PART_SIZE).contains(&part_size),
+            "the part size should be between {} and {} bytes",
+            constants::MIN_PART_SIZE,
+            constants::MAX_PART_SIZE
+        );
+        ensure!(
+            file_size.div_ceil(part_size) <= constants::MAX_PART_COUNT,
+            "the part count should not exceed {}",
+            constants::MAX_PART_COUNT
+        );
+        Ok(part_size)
+    }
+
+    // the part size of a stream of unknown length doubles every
+    // STREAM_PARTS_PER_STEP parts up to STREAM_MAX_PART_SIZE, so starting from
+    // PART_SIZE the parts hold ~3.8TB before MAX_PART_COUNT is reached
+    pub fn stream_part_size(part_size: u64, part_number: u16) -> u64 {
+        let step = (part_number.max(1) as u64 - 1) / constants::STREAM_PARTS_PER_STEP;
+        part_size
+            .checked_shl(step as u32)
+            .unwrap_or(u64::MAX)
+            .min(constants::STREAM_MAX_PART_SIZE)
+    }
+
+    // the part size the upload was created with, every part but the last has
+    // exactly that size; the size part_size_for picks is kept when the uploaded
+    // parts agree with it, otherwise the largest uploaded part gives it away
+    pub fn resumed_part_size(
+        file_size: u64,
+        uploaded: &[UploadedParts],
+        part_size: Option<u64>,
+    ) -> Result<u64> {
+        let fits = |size: u64| {
+            Self::part_size_for(file_size, Some(size)).is_ok()
+                && uploaded.iter().all(|part| {
+                    let start = (part.part_number as u64)
+                        .checked_sub(1)
+                        .and_then(|index| index.checked_mul(size));
+                    match start {
+                        Some(start) if start < file_size => {
+                            part.part_size == size.min(file_size - start)
+                        }
+                        _ => false,
+                    }
+                })
+        };
+        let expected = Self::part_size_for(file_size, part_size)?;
+        if fits(expected) {
+            return Ok(expected);
+        }
+        uploaded
+            .iter()
+            .map(|part| part.part_size)
+            .max()
+            .filter(|size| fits(*size))
+            .ok_or_else(|| anyhow!("the uploaded parts don't match a {} bytes file", file_size))
+    }
+
+    pub fn create_part_info_list(size: u64, part_size: u64) -> Result<Vec<PartInfo>> {
+        ensure!(part_size > 0, "the part size should not be zero");
+        let count = size.div_ceil(part_size);
+        ensure!(
+            count <= constants::MAX_PART_COUNT,
+            "the part count should not exceed {}",
+            constants::MAX_PART_COUNT
+        );
         let parts = (1..=count)
             .map(|index| PartInfo {
                 part_number: index as u16,
@@ -334,8 +496,8 @@ impl ADriveCoreAPI {
 
     pub async fn update_file(
         &self,
-        drive_id: &str,
-        file_id: &str,
+        drive_id: &DriveId,
+        file_id: &FileId,
         name: Option<&str>,
         if_name_exists: Option<IfNameExists>,
         starred: Option<bool>,
@@ -348,44 +510,125 @@ impl ADriveCoreAPI {
 
     pub async fn move_file(
         &self,
-        drive_id: &str,
-        file_id: &str,
-        target_parent_id: &str,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        target_parent_id: &FileId,
         rename: Option<&str>,
+        check_name_mode: Option<IfNameExists>,
     ) -> Result<AsyncTaskResponse> {
         let token = &self.auth.refresh_if_needed().await?;
-        MoveFileRequest::new(drive_id, file_id, target_parent_id, rename)
+        MoveFileRequest::new(drive_id, file_id, target_parent_id, rename, check_name_mode)
             .dispatch(None, Some(&token.access_token))
             .await
     }
 
     pub async fn copy_file(
         &self,
-        drive_id: &str,
-        file_id: &str,
-        target_parent_id: &str,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        target_parent_id: &FileId,
+        auto_rename: bool,
     ) -> Result<AsyncTaskResponse> {
         let token = &self.auth.refresh_if_needed().await?;
-        CopyFileRequest::new(drive_id, file_id, target_parent_id)
+        CopyFileRequest::new(drive_id, file_id, target_parent_id, auto_rename)
             .dispatch(None, Some(&token.access_token))
             .await
     }
 
-    pub async fn recycle_file(&self, drive_id: &str, file_id: &str) -> Result<AsyncTaskResponse> {
+    pub async fn recycle_file(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+    ) -> Result<AsyncTaskResponse> {
         let token = &self.auth.refresh_if_needed().await?;
         RecycleFileRequest::new(drive_id, file_id)
             .dispatch(None, Some(&token.access_token))
             .await
     }
 
-    pub async fn delete_file(&self, drive_id: &str, file_id: &str) -> Result<AsyncTaskResponse> {
+    pub async fn delete_file(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+    ) -> Result<AsyncTaskResponse> {
         let token = &self.auth.refresh_if_needed().await?;
         DeleteFileRequest { drive_id, file_id }
             .dispatch(None, Some(&token.access_token))
             .await
     }
 
-    pub async fn get_async_task_state(&self, task_id: &str) -> Result<GetAsyncTaskStateResponse> {
+    pub async fn list_recyclebin(
+        &self,
+        drive_id: &DriveId,
+        marker: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<ListFilesResponse> {
+        let token = &self.auth.refresh_if_needed().await?;
+        ListRecycleBinRequest::new(drive_id, marker, limit)
+            .dispatch(None, Some(&token.access_token))
+            .await
+    }
+
+    pub async fn restore_file(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+    ) -> Result<AsyncTaskResponse> {
+        let token = &self.auth.refresh_if_needed().await?;
+        RestoreFileRequest { drive_id, file_id }
+            .dispatch(None, Some(&token.access_token))
+            .await
+    }
+
+    pub async fn clear_recyclebin(&self, drive_id: &DriveId) -> Result<ClearRecycleBinResponse> {
+        let token = &self.auth.refresh_if_needed().await?;
+        ClearRecycleBinRequest { drive_id }
+            .dispatch(None, Some(&token.access_token))
+            .await
+    }
+
+    pub async fn get_video_preview_play_info(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        url_expire_sec: Option<u64>,
+    ) -> Result<GetVideoPreviewPlayInfoResponse> {
+        let token = &self.auth.refresh_if_needed().await?;
+        let requested_at = Utc::now();
+        let mut resp = GetVideoPreviewPlayInfoRequest::new(drive_id, file_id, url_expire_sec)
+            .dispatch(None, Some(&token.access_token))
+            .await?;
+        let url_expire_sec =
+            url_expire_sec.unwrap_or(GetVideoPreviewPlayInfoRequest::DEFAULT_URL_EXPIRE_SEC);
+        resp.video_preview_play_info.expires_at =
+            Some(requested_at + chrono::Duration::seconds(url_expire_sec as i64));
+        Ok(resp)
+    }
+
+    pub async fn update_video_record(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        play_cursor: Duration,
+        duration: Option<Duration>,
+    ) -> Result<UpdateVideoRecordResponse> {
+        let token = &self.auth.refresh_if_needed().await?;
+        UpdateVideoRecordRequest::new(drive_id, file_id, play_cursor, duration)
+            .dispatch(None, Some(&token.access_token))
+            .await
+    }
+
+    pub async fn list_recent_videos(&self) -> Result<ListFilesResponse> {
+        let token = &self.auth.refresh_if_needed().await?;
+        ListRecentVideosRequest::default()
+            .dispatch(None, Some(&token.access_token))
+            .await
+    }
+
+    pub async fn get_async_task_state(
+        &self,
+        task_id: &TaskId,
+    ) -> Result<GetAsyncTaskStateResponse> {
         let token = &self.auth.refresh_if_needed().await?;
         GetAsyncTaskStateRequest {
             async_task_id: task_id,
@@ -396,19 +639,23 @@ impl ADriveCoreAPI {
 
     pub async fn check_pre_hash(
         &self,
-        drive_id: &str,
-        parent_file_id: &str,
+        drive_id: &DriveId,
+        parent_file_id: &FileId,
         file_name: &str,
         part_info_list: Vec<PartInfo>,
         pre_hash: &str,
         size: u64,
+        options: &UploadOptions,
     ) -> Result<CreateFileResponse> {
         let token = &self.auth.refresh_if_needed().await?;
+        let local_created_at = options.local_created_at.as_ref().map(utils::format_time);
+        let local_modified_at = options.local_modified_at.as_ref().map(utils::format_time);
         CreateFileRequest::new(
             drive_id,
             parent_file_id,
             file_name,
             FileType::File,
+            Some(options.if_name_exists),
             Some(part_info_list),
             Some(pre_hash),
             Some(size),
@@ -416,6 +663,8 @@ impl ADriveCoreAPI {
             None,
             None,
             None,
+            local_created_at.as_deref(),
+            local_modified_at.as_deref(),
         )
         .dispatch(None, Some(&token.access_token))
         .await
@@ -423,20 +672,24 @@ impl ADriveCoreAPI {
 
     pub async fn check_content_hash(
         &self,
-        drive_id: &str,
-        parent_file_id: &str,
+        drive_id: &DriveId,
+        parent_file_id: &FileId,
         file_name: &str,
         part_info_list: Vec<PartInfo>,
         content_hash: &str,
         proof_code: &str,
         size: u64,
+        options: &UploadOptions,
     ) -> Result<CreateFileResponse> {
         let token = &self.auth.refresh_if_needed().await?;
+        let local_created_at = options.local_created_at.as_ref().map(utils::format_time);
+        let local_modified_at = options.local_modified_at.as_ref().map(utils::format_time);
         CreateFileRequest::new(
             drive_id,
             parent_file_id,
             file_name,
             FileType::File,
+            Some(options.if_name_exists),
             Some(part_info_list),
             None,
             Some(size),
@@ -444,6 +697,8 @@ impl ADriveCoreAPI {
             Some("v1"),
             Some(content_hash),
             Some("sha1"),
+            local_created_at.as_deref(),
+            local_modified_at.as_deref(),
         )
         .dispatch(None, Some(&token.access_token))
         .await
@@ -461,14 +716,70 @@ impl ADriveCoreAPI {
 
     pub async fn upload_file(
         &self,
-        drive_id: &str,
-        parent_file_id: &str,
+        drive_id: &DriveId,
+        parent_file_id: &FileId,
         file_name: &str,
         file: &mut fs::File,
-    ) -> Result<()> {
-        let file_size = file.metadata()?.size();
-        let part_info_list = Self::create_part_info_list(file_size)?;
+        options: &UploadOptions,
+    ) -> Result<UploadOutcome> {
+        let metadata = file.metadata()?;
+        let mut options = options.clone();
+        if options.preserve_timestamps {
+            options.local_created_at = options
+                .local_created_at
+                .or_else(|| metadata.created().ok().map(DateTime::from));
+            options.local_modified_at = options
+                .local_modified_at
+                .or_else(|| metadata.modified().ok().map(DateTime::from));
+        }
+        self.upload_seekable(
+            drive_id,
+            parent_file_id,
+            file_name,
+            file,
+            metadata.size(),
+            &options,
+        )
+        .await
+    }
+
+    pub async fn upload_bytes(
+        &self,
+        drive_id: &DriveId,
+        parent_file_id: &FileId,
+        file_name: &str,
+        data: Bytes,
+        options: &UploadOptions,
+    ) -> Result<FileEntry> {
+        let size = data.len() as u64;
+        let outcome = self
+            .upload_seekable(
+                drive_id,
+                parent_file_id,
+                file_name,
+                &mut Cursor::new(data),
+                size,
+                options,
+            )
+            .await?;
+        Ok(outcome.file)
+    }
+
+    async fn upload_seekable<R: Read + Seek>(
+        &self,
+        drive_id: &DriveId,
+        parent_file_id: &FileId,
+        file_name: &str,
+        file: &mut R,
+        file_size: u64,
+        options: &UploadOptions,
+    ) -> Result<UploadOutcome> {
+        let progress = ProgressTracker::new(options.progress.clone(), file_name, Some(file_size));
+        let part_size = Self::part_size_for(file_size, options.part_size)?;
+        let part_info_list = Self::create_part_info_list(file_size, part_size)?;
+        progress.phase(Phase::Hashing);
         let pre_hash = utils::get_pre_hash(file)?;
+        progress.phase(Phase::RapidCheck);
         let resp = self
             .check_pre_hash(
                 drive_id,
@@ -477,12 +788,16 @@ impl ADriveCoreAPI {
                 part_info_list.clone(),
                 &pre_hash,
                 file_size,
+                options,
             )
             .await?;
+        ensure!(!resp.exist(), "{} already exists", file_name);
         if resp.pre_hash_matched() {
+            progress.phase(Phase::Hashing);
             let content_hash = utils::get_content_hash(file)?;
             let token = self.auth.refresh_if_needed().await?;
             let proof_code = utils::get_proof_code(file, file_size, &token.access_token)?;
+            progress.phase(Phase::RapidCheck);
             let resp = self
                 .check_content_hash(
                     drive_id,
@@ -492,10 +807,20 @@ impl ADriveCoreAPI {
                     &content_hash,
                     &proof_code,
                     file_size,
+                    options,
                 )
                 .await?;
+            ensure!(!resp.exist(), "{} already exists", file_name);
             if resp.content_hash_matched() {
-                Ok(())
+                let file = self
+                    .get_file_by_id(drive_id, &resp.file_id()?, None)
+                    .await?;
+                progress.phase(Phase::Done);
+                Ok(UploadOutcome {
+                    file,
+                    kind: UploadKind::Rapid,
+                    bytes_sent: 0,
+                })
             } else {
                 self.multipart_upload_file(
                     drive_id,
@@ -504,6 +829,7 @@ impl ADriveCoreAPI {
                     file_size,
                     file,
                     Some(resp),
+                    options,
                 )
                 .await
             }
@@ -515,52 +841,169 @@ impl ADriveCoreAPI {
                 file_size,
                 file,
                 Some(resp),
+                options,
             )
             .await
         }
     }
 
-    pub async fn multipart_upload_file(
+    pub async fn multipart_upload_file<R: Read + Seek>(
         &self,
-        drive_id: &str,
-        parent_file_id: &str,
+        drive_id: &DriveId,
+        parent_file_id: &FileId,
         file_name: &str,
         file_size: u64,
-        file: &mut fs::File,
+        file: &mut R,
         created_file: Option<CreateFileResponse>,
-    ) -> Result<()> {
+        options: &UploadOptions,
+    ) -> Result<UploadOutcome> {
+        let progress = ProgressTracker::new(options.progress.clone(), file_name, Some(file_size));
+        let part_size = Self::part_size_for(file_size, options.part_size)?;
         let file_id;
         let upload_id;
         let part_info_list_with_upload_url;
 
-        if created_file.is_none() {
-            let part_info_list = Self::create_part_info_list(file_size)?;
+        if let Some(response) = created_file {
+            file_id = response.file_id()?;
+            upload_id = response.upload_id()?;
+            part_info_list_with_upload_url = response.part_info_list()?;
+        } else {
+            let part_info_list = Self::create_part_info_list(file_size, part_size)?;
             let resp = self
-                .create_multipart_upload(drive_id, parent_file_id, file_name, Some(part_info_list))
+                .create_multipart_upload(
+                    drive_id,
+                    parent_file_id,
+                    file_name,
+                    Some(part_info_list),
+                    options,
+                )
                 .await?;
-            file_id = resp.file_id();
-            upload_id = resp.upload_id();
-            part_info_list_with_upload_url = resp.part_info_list();
-        } else {
-            let response = created_file.unwrap();
-            file_id = response.file_id();
-            upload_id = response.upload_id();
-            part_info_list_with_upload_url = response.part_info_list();
+            ensure!(!resp.exist(), "{} already exists", file_name);
+            file_id = resp.file_id()?;
+            upload_id = resp.upload_id()?;
+            part_info_list_with_upload_url = resp.part_info_list()?;
+        }
+        if let Some(upload_started) = &options.upload_started {
+            upload_started(&file_id, &upload_id);
+        }
+
+        let bytes_sent = self
+            .upload_parts(
+                file,
+                &part_info_list_with_upload_url,
+                part_size,
+                &progress,
+                options,
+            )
+            .await?;
+        progress.phase(Phase::Completing);
+        let uploaded = self
+            .list_uploaded_parts(drive_id, &file_id, &upload_id)
+            .await?;
+        ensure!(
+            uploaded.len() == part_info_list_with_upload_url.len(),
+            "part upload failed"
+        );
+        let file = self
+            .complete_multipart_upload(drive_id, &file_id, &upload_id)
+            .await?;
+        progress.phase(Phase::Done);
+        Ok(UploadOutcome {
+            file,
+            kind: UploadKind::Transferred,
+            bytes_sent,
+        })
+    }
+
+    // continues an unfinished multipart upload, only the parts the server
+    // hasn't received yet are sent
+    pub async fn resume_upload<R: Read + Seek>(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        upload_id: &UploadId,
+        file_size: u64,
+        file: &mut R,
+        options: &UploadOptions,
+    ) -> Result<UploadOutcome> {
+        let uploaded = self
+            .list_uploaded_parts(drive_id, file_id, upload_id)
+            .await?;
+        let part_size = Self::resumed_part_size(file_size, &uploaded, options.part_size)?;
+        let part_info_list = Self::create_part_info_list(file_size, part_size)?;
+        let missing: Vec<u16> = part_info_list
+            .iter()
+            .map(|part_info| part_info.part_number)
+            .filter(|part_number| !uploaded.iter().any(|p| p.part_number == *part_number))
+            .collect();
+        let remaining = file_size.saturating_sub(uploaded.iter().map(|p| p.part_size).sum());
+        let progress =
+            ProgressTracker::new(options.progress.clone(), file_id.as_str(), Some(remaining));
+
+        let mut bytes_sent = 0;
+        if !missing.is_empty() {
+            let resp = self
+                .flush_upload_url(drive_id, file_id, upload_id, &missing)
+                .await?;
+            bytes_sent = self
+                .upload_parts(file, &resp.part_info_list, part_size, &progress, options)
+                .await?;
+            progress.phase(Phase::Completing);
+            let uploaded = self
+                .list_uploaded_parts(drive_id, file_id, upload_id)
+                .await?;
+            ensure!(uploaded.len() == part_info_list.len(), "part upload failed");
         }
+        let file = self
+            .complete_multipart_upload(drive_id, file_id, upload_id)
+            .await?;
+        progress.phase(Phase::Done);
+        let kind = if missing.len() == part_info_list.len() {
+            UploadKind::Transferred
+        } else {
+            UploadKind::Resumed
+        };
+        Ok(UploadOutcome {
+            file,
+            kind,
+            bytes_sent,
+        })
+    }
 
-        for part_info in part_info_list_with_upload_url.iter() {
+    async fn upload_parts<R: Read + Seek>(
+        &self,
+        file: &mut R,
+        part_info_list: &[PartInfo],
+        part_size: u64,
+        progress: &ProgressTracker,
+        options: &UploadOptions,
+    ) -> Result<u64> {
+        let mut bytes_sent = 0;
+        for part_info in part_info_list.iter() {
             let mut buffer = Vec::new();
-            let pos = (part_info.part_number as u64 - 1) * ADriveCoreAPI::PART_SIZE;
-            let _ = file.seek(SeekFrom::Start(pos));
-            let _ = file.take(ADriveCoreAPI::PART_SIZE).read_to_end(&mut buffer);
-            self.upload_part(part_info, buffer).await?;
+            let pos = (part_info.part_number as u64 - 1) * part_size;
+            file.seek(SeekFrom::Start(pos))?;
+            file.take(part_size).read_to_end(&mut buffer)?;
+            let len = buffer.len() as u64;
+            self.upload_part(part_info, buffer, options.bandwidth_limit.as_ref())
+                .await?;
+            bytes_sent += len;
+            progress.advance(Phase::Uploading, len, part_info.part_number as u32);
         }
+        Ok(bytes_sent)
+    }
 
+    pub(crate) async fn list_uploaded_parts(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        upload_id: &UploadId,
+    ) -> Result<Vec<UploadedParts>> {
         let mut marker = None;
         let mut uploaded = Vec::new();
         loop {
             let resp = self
-                .list_multipart_uploads(drive_id, &file_id, &upload_id, marker)
+                .list_multipart_uploads(drive_id, file_id, upload_id, marker)
                 .await?;
             uploaded.extend(resp.uploaded_parts);
             marker = Some(resp.next_part_number_marker);
@@ -568,13 +1011,211 @@ impl ADriveCoreAPI {
                 break;
             }
         }
+        Ok(uploaded)
+    }
+
+    async fn read_part<R: AsyncRead + Unpin>(reader: &mut R, part_size: u64) -> Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        reader.take(part_size).read_to_end(&mut buffer).await?;
+        Ok(buffer)
+    }
+
+    // for sources which can't be seeked, so rapid upload is skipped and parts are
+    // read one by one into memory, at most STREAM_MAX_PART_SIZE each; when size
+    // is None the upload url of each part is requested as the part is read and
+    // the parts grow as described in stream_part_size
+    pub async fn upload_stream<R: AsyncRead + Unpin + Send>(
+        &self,
+        drive_id: &DriveId,
+        parent_file_id: &FileId,
+        file_name: &str,
+        reader: &mut R,
+        size: Option<u64>,
+        options: &UploadOptions,
+    ) -> Result<FileEntry> {
+        let progress = ProgressTracker::new(options.progress.clone(), file_name, size);
+        // an unknown size only validates the part size, the parts grow later
+        let part_size = Self::part_size_for(size.unwrap_or(0), options.part_size)?;
         ensure!(
-            uploaded.len() == part_info_list_with_upload_url.len(),
-            "part upload failed"
+            part_size <= constants::STREAM_MAX_PART_SIZE,
+            "the part size of a stream should not exceed {} bytes",
+            constants::STREAM_MAX_PART_SIZE
         );
-        let _ = self
+        let part_size_of = |part_number| match size {
+            Some(_) => part_size,
+            None => Self::stream_part_size(part_size, part_number),
+        };
+        let mut buffer = Self::read_part(reader, part_size).await?;
+        let part_info_list = match size {
+            Some(size) => Self::create_part_info_list(size, part_size)?,
+            None => Self::create_part_info_list(buffer.len() as u64, part_size)?,
+        };
+        let resp = self
+            .create_multipart_upload(
+                drive_id,
+                parent_file_id,
+                file_name,
+                Some(part_info_list),
+                options,
+            )
+            .await?;
+        ensure!(!resp.exist(), "{} already exists", file_name);
+        let file_id = resp.file_id()?;
+        let upload_id = resp.upload_id()?;
+        let mut part_info_list = resp.part_info_list()?.into_iter();
+
+        let mut part_number = 1_u16;
+        let mut uploaded_size = 0_u64;
+        while !buffer.is_empty() {
+            ensure!(
+                part_number as u64 <= constants::MAX_PART_COUNT,
+                "the stream doesn't fit in {} parts after {} bytes",
+                constants::MAX_PART_COUNT,
+                uploaded_size
+            );
+            let part_info = match part_info_list.next() {
+                Some(part_info) => part_info,
+                None => self
+                    .flush_upload_url(drive_id, &file_id, &upload_id, &[part_number])
+                    .await?
+                    .part_info_list
+                    .remove(0),
+            };
+            let len = buffer.len() as u64;
+            self.upload_part(&part_info, buffer, options.bandwidth_limit.as_ref())
+                .await?;
+            uploaded_size += len;
+            progress.advance(Phase::Uploading, len, part_number as u32);
+            part_number += 1;
+            buffer = Self::read_part(reader, part_size_of(part_number)).await?;
+        }
+        if let Some(size) = size {
+            ensure!(
+                uploaded_size == size,
+                "stream size mismatch, expected {} bytes but read {}",
+                size,
+                uploaded_size
+            );
+        }
+        progress.phase(Phase::Completing);
+        let file = self
             .complete_multipart_upload(drive_id, &file_id, &upload_id)
             .await?;
-        Ok(())
+        progress.phase(Phase::Done);
+        Ok(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ADriveCoreAPI, UploadedParts};
+    use crate::constants::{MAX_PART_COUNT, MAX_PART_SIZE, MIN_PART_SIZE, STREAM_MAX_PART_SIZE};
+
+    const MB: u64 = 1024 * 1024;
+
+    #[test]
+    fn part_size_for_small_files() {
+        for size in [0, 1, ADriveCoreAPI::PART_SIZE] {
+            let part_size = ADriveCoreAPI::part_size_for(size, None).unwrap();
+            assert_eq!(part_size, ADriveCoreAPI::PART_SIZE);
+        }
+    }
+
+    #[test]
+    fn part_size_for_large_files() {
+        let limit = ADriveCoreAPI::PART_SIZE * MAX_PART_COUNT;
+        assert_eq!(
+            ADriveCoreAPI::part_size_for(limit, None).unwrap(),
+            ADriveCoreAPI::PART_SIZE
+        );
+        let part_size = ADriveCoreAPI::part_size_for(limit + 1, None).unwrap();
+        assert_eq!(part_size, ADriveCoreAPI::PART_SIZE + MB);
+        assert!((limit + 1).div_ceil(part_size) <= MAX_PART_COUNT);
+
+        let max = MAX_PART_SIZE * MAX_PART_COUNT;
+        assert_eq!(
+            ADriveCoreAPI::part_size_for(max, None).unwrap(),
+            MAX_PART_SIZE
+        );
+        assert!(ADriveCoreAPI::part_size_for(max + 1, None).is_err());
+    }
+
+    #[test]
+    fn stream_part_size_grows() {
+        let part_size = ADriveCoreAPI::PART_SIZE;
+        assert_eq!(ADriveCoreAPI::stream_part_size(part_size, 1), part_size);
+        assert_eq!(ADriveCoreAPI::stream_part_size(part_size, 1_000), part_size);
+        assert_eq!(
+            ADriveCoreAPI::stream_part_size(part_size, 1_001),
+            part_size * 2
+        );
+        assert_eq!(
+            ADriveCoreAPI::stream_part_size(part_size, MAX_PART_COUNT as u16),
+            STREAM_MAX_PART_SIZE
+        );
+        let total: u64 = (1..=MAX_PART_COUNT as u16)
+            .map(|part_number| ADriveCoreAPI::stream_part_size(part_size, part_number))
+            .sum();
+        assert_eq!(total, 4_032_000 * MB);
+    }
+
+    #[test]
+    fn part_size_for_override() {
+        assert_eq!(
+            ADriveCoreAPI::part_size_for(MB, Some(MIN_PART_SIZE)).unwrap(),
+            MIN_PART_SIZE
+        );
+        assert!(ADriveCoreAPI::part_size_for(MB, Some(MIN_PART_SIZE - 1)).is_err());
+        assert!(ADriveCoreAPI::part_size_for(MB, Some(MAX_PART_SIZE + 1)).is_err());
+        let size = MIN_PART_SIZE * MAX_PART_COUNT;
+        assert!(ADriveCoreAPI::part_size_for(size, Some(MIN_PART_SIZE)).is_ok());
+        assert!(ADriveCoreAPI::part_size_for(size + 1, Some(MIN_PART_SIZE)).is_err());
+    }
+
+    #[test]
+    fn create_part_info_list_boundaries() {
+        let part_size = ADriveCoreAPI::PART_SIZE;
+        let count = |size| {
+            ADriveCoreAPI::create_part_info_list(size, part_size)
+                .unwrap()
+                .len()
+        };
+        assert_eq!(count(0), 0);
+        assert_eq!(count(1), 1);
+        assert_eq!(count(part_size), 1);
+        assert_eq!(count(part_size + 1), 2);
+
+        let parts =
+            ADriveCoreAPI::create_part_info_list(part_size * MAX_PART_COUNT, part_size).unwrap();
+        assert_eq!(parts.first().unwrap().part_number, 1);
+        assert_eq!(parts.last().unwrap().part_number as u64, MAX_PART_COUNT);
+        assert!(
+            ADriveCoreAPI::create_part_info_list(part_size * MAX_PART_COUNT + 1, part_size)
+                .is_err()
+        );
+        assert!(ADriveCoreAPI::create_part_info_list(1, 0).is_err());
+    }
+
+    #[test]
+    fn resumed_part_size_follows_the_uploaded_parts() {
+        let part = |part_number, part_size| UploadedParts {
+            etag: String::new(),
+            part_number,
+            part_size,
+        };
+        let part_size = ADriveCoreAPI::PART_SIZE;
+        let file_size = 2 * part_size + MB;
+        let resumed = |uploaded: &[UploadedParts], option| {
+            ADriveCoreAPI::resumed_part_size(file_size, uploaded, option)
+        };
+        assert_eq!(resumed(&[], None).unwrap(), part_size);
+        assert_eq!(resumed(&[part(3, MB)], None).unwrap(), part_size);
+        // started with a different part size than the one asked for now
+        let uploaded = [part(1, 4 * MB), part(2, 4 * MB)];
+        assert_eq!(resumed(&uploaded, None).unwrap(), 4 * MB);
+        assert_eq!(resumed(&uploaded, Some(8 * MB)).unwrap(), 4 * MB);
+        assert!(resumed(&[part(1, part_size), part(2, 4 * MB)], None).is_err());
+        assert!(resumed(&[part(4, part_size)], None).is_err());
+        assert!(resumed(&[part(0, part_size)], None).is_err());
     }
 }
@@ -38,7 +38,7 @@ impl Request for GetQRCodeRequest<'_> {
     type Response = GetQRCodeResponse;
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GetQRCodeResponse {
     #[serde(rename = "qrCodeUrl")]
     pub qr_code_url: String,
@@ -63,15 +63,30 @@ impl Request for GetQRCodeImageRequest<'_> {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GetQRCodeImageResponse {}
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum QRCodeStatus {
     WaitLogin,
     ScanSuccess,
     LoginSuccess,
     QRCodeExpired,
+    // a status this client does not know yet
+    Unknown(String),
+}
+
+impl QRCodeStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            QRCodeStatus::WaitLogin => "WaitLogin",
+            QRCodeStatus::ScanSuccess => "ScanSuccess",
+            QRCodeStatus::LoginSuccess => "LoginSuccess",
+            QRCodeStatus::QRCodeExpired => "QRCodeExpired",
+            QRCodeStatus::Unknown(s) => s,
+        }
+    }
 }
 
 impl From<&str> for QRCodeStatus {
@@ -81,11 +96,23 @@ impl From<&str> for QRCodeStatus {
             "ScanSuccess" => QRCodeStatus::ScanSuccess,
             "LoginSuccess" => QRCodeStatus::LoginSuccess,
             "QRCodeExpired" => QRCodeStatus::QRCodeExpired,
-            _ => unimplemented!(),
+            _ => QRCodeStatus::Unknown(value.to_string()),
         }
     }
 }
 
+impl Serialize for QRCodeStatus {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for QRCodeStatus {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(String::deserialize(deserializer)?.as_str().into())
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct GetQRCodeStatusRequest<'a> {
     #[serde(skip_serializing)]
@@ -103,7 +130,7 @@ impl Request for GetQRCodeStatusRequest<'_> {
         Ok(path)
     }
 }
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GetQRCodeStatusResponse {
     pub status: QRCodeStatus,
     #[serde(rename = "authCode")]
@@ -161,7 +188,7 @@ impl Request for GetAccessTokenRequest<'_> {
     type Response = GetAccessTokenResponse;
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GetAccessTokenResponse {
     pub token_type: String,
     pub access_token: String,
@@ -206,3 +233,23 @@ impl Request for GetAccessTokenRequest3 {
     const METHOD: Method = Method::POST;
     type Response = GetAccessTokenResponse;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_qr_code_status() {
+        let resp: GetQRCodeStatusResponse =
+            serde_json::from_str(r#"{"status": "LoginSuccess", "authCode": "abc"}"#).unwrap();
+        assert_eq!(resp.status, QRCodeStatus::LoginSuccess);
+
+        let resp: GetQRCodeStatusResponse =
+            serde_json::from_str(r#"{"status": "ScanCanceled"}"#).unwrap();
+        assert_eq!(
+            resp.status,
+            QRCodeStatus::Unknown("ScanCanceled".to_string())
+        );
+        assert_eq!(serde_json::to_value(&resp.status).unwrap(), "ScanCanceled");
+    }
+}
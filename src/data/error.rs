@@ -1,8 +1,8 @@
 use std::{error, fmt};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ErrorResponse {
     pub code: String,
     pub message: String,
@@ -17,3 +17,22 @@ impl fmt::Display for ErrorResponse {
         write!(f, "err type: {}, err message: {}", self.code, self.message)
     }
 }
+
+#[derive(Debug)]
+pub struct InsufficientSpaceError {
+    pub needed: u64,
+    pub available: u64,
+    pub reserved: u64,
+}
+
+impl error::Error for InsufficientSpaceError {}
+
+impl fmt::Display for InsufficientSpaceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "insufficient space: {} bytes needed, {} bytes available, {} bytes reserved",
+            self.needed, self.available, self.reserved
+        )
+    }
+}
@@ -1,6 +1,13 @@
-use super::Request;
+use super::media::seconds;
+use super::{
+    DriveId, FileId, ImageMediaMetadata, Request, TaskId, UploadId, VideoMediaMetadata,
+    VideoPreviewMetadata,
+};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[derive(Serialize, Default, Debug)]
 #[serde(rename_all = "snake_case")]
@@ -21,50 +28,133 @@ pub enum SortBy {
     Asc,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum FileType {
     Folder,
     File,
-    #[default]
-    #[serde(skip_deserializing)]
-    All,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+// what the server makes of a file's extension, folders have none
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Category {
+    Video,
+    Audio,
+    Image,
+    Doc,
+    Zip,
+    App,
+    Others,
+    Unknown(String),
+}
+
+impl Category {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Category::Video => "video",
+            Category::Audio => "audio",
+            Category::Image => "image",
+            Category::Doc => "doc",
+            Category::Zip => "zip",
+            Category::App => "app",
+            Category::Others => "others",
+            Category::Unknown(s) => s,
+        }
+    }
+}
+
+impl From<String> for Category {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "video" => Category::Video,
+            "audio" => Category::Audio,
+            "image" => Category::Image,
+            "doc" => Category::Doc,
+            "zip" => Category::Zip,
+            "app" => Category::App,
+            "others" => Category::Others,
+            _ => Category::Unknown(s),
+        }
+    }
+}
+
+impl Serialize for Category {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Category {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(String::deserialize(deserializer)?.into())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum IfNameExists {
     #[default]
     AutoRename,
     Refuse,
     Ignore,
+    // not a server mode, the existing file is recycled once the new one is in place
+    #[serde(rename(serialize = "ignore"), skip_deserializing)]
+    Replace,
 }
 
+// the thumbnail urls of file entries are generated for these settings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ThumbnailOptions {
+    // px, for images and videos
+    pub width: Option<u32>,
+    // the frame of a video to take, from its start
+    pub video_time: Option<Duration>,
+}
+
+// flattened into the requests which return file entries
 #[derive(Debug, Serialize, Default)]
+struct ThumbnailFields {
+    video_thumbnail_time: Option<u32>,  // ms
+    video_thumbnail_width: Option<u32>, // px
+    image_thumbnail_width: Option<u32>, // px
+}
+
+impl From<Option<&ThumbnailOptions>> for ThumbnailFields {
+    fn from(options: Option<&ThumbnailOptions>) -> Self {
+        let options = options.copied().unwrap_or_default();
+        Self {
+            video_thumbnail_time: options.video_time.map(|t| t.as_millis() as u32),
+            video_thumbnail_width: options.width,
+            image_thumbnail_width: options.width,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
 pub struct ListFilesRequest<'a> {
-    drive_id: &'a str,
-    parent_file_id: &'a str,
+    drive_id: &'a DriveId,
+    parent_file_id: &'a FileId,
     limit: Option<u32>, // 50..=100
     marker: Option<&'a str>,
     order_by: Option<OrderBy>,
     order_direction: Option<SortBy>,
     category: Option<&'a str>, // TODO
     r#type: Option<FileType>,
-    video_thumbnail_time: Option<u32>,  // ms
-    video_thumbnail_width: Option<u32>, // px
-    image_thumbnail_width: Option<u32>, // px
-    fields: Option<&'a str>,            // TODO *
+    #[serde(flatten)]
+    thumbnail: ThumbnailFields,
+    fields: Option<&'a str>, // TODO *
 }
 
 impl<'a> ListFilesRequest<'a> {
     pub fn new(
-        drive_id: &'a str,
-        parent_file_id: &'a str,
+        drive_id: &'a DriveId,
+        parent_file_id: &'a FileId,
         marker: Option<&'a str>,
         order_by: Option<OrderBy>,
         order_direction: Option<SortBy>,
         category: Option<&'a str>,
         r#type: Option<FileType>,
+        thumbnail: Option<&ThumbnailOptions>,
     ) -> Self {
         Self {
             drive_id,
@@ -74,7 +164,9 @@ impl<'a> ListFilesRequest<'a> {
             order_direction,
             category,
             r#type,
-            ..Default::default()
+            thumbnail: thumbnail.into(),
+            limit: None,
+            fields: None,
         }
     }
 }
@@ -84,38 +176,53 @@ impl Request for ListFilesRequest<'_> {
     const METHOD: reqwest::Method = Method::POST;
     type Response = ListFilesResponse;
 }
-#[derive(Debug, Deserialize)]
-pub struct VideoMediaMetadata {
-    pub width: u32,
-    pub height: u32,
-    pub duration: Option<String>,
-    pub video_media_video_stream: Vec<serde_json::Value>,
-    pub video_media_audio_stream: Vec<serde_json::Value>,
-    pub time: Option<String>,
-}
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FileEntry {
-    pub drive_id: String,
-    pub file_id: String,
-    pub parent_file_id: String,
+    pub drive_id: DriveId,
+    pub file_id: FileId,
+    pub parent_file_id: FileId,
     pub name: String,
-    pub size: Option<u64>,              // TODO folder don't have size
-    pub file_extension: Option<String>, // TODO folder don't have file_extension
-    pub content_hash: Option<String>,   // TODO folder don't have content_hash
-    pub category: Option<String>,       // TODO folder don't have category
+    pub size: Option<u64>,              // folders don't have size
+    pub file_extension: Option<String>, // folders don't have file_extension
+    pub content_hash: Option<String>,   // folders don't have content_hash
+    pub category: Option<Category>,     // folders don't have category
     pub r#type: FileType,
     pub thumbnail: Option<String>,
     pub url: Option<String>,
     pub download_url: Option<String>, // TODO complete file needed
-    pub created_at: String,
-    pub updated_at: String,
-    pub play_cursor: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default, deserialize_with = "optional_time")]
+    pub local_created_at: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "optional_time")]
+    pub local_modified_at: Option<DateTime<Utc>>,
+    // where the video was left off
+    #[serde(default, with = "seconds")]
+    pub play_cursor: Option<Duration>,
     pub video_media_metadata: Option<VideoMediaMetadata>,
-    pub video_preview_metadata: Option<String>,
+    pub video_preview_metadata: Option<VideoPreviewMetadata>,
+    pub image_media_metadata: Option<ImageMediaMetadata>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+impl FileEntry {
+    pub fn is_dir(&self) -> bool {
+        self.r#type == FileType::Folder
+    }
+}
+
+// local times are only set for uploads which passed them and media times
+// aren't always known, either may come as an empty string
+pub(crate) fn optional_time<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error> {
+    match Option::<String>::deserialize(deserializer)?.filter(|s| !s.is_empty()) {
+        Some(s) => Ok(Some(s.parse().map_err(serde::de::Error::custom)?)),
+        None => Ok(None),
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ListFilesResponse {
     pub items: Vec<FileEntry>,
     #[serde(default)]
@@ -124,9 +231,9 @@ pub struct ListFilesResponse {
     pub total_count: Option<u32>,
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
 pub struct SearchFilesRequest<'a> {
-    drive_id: &'a str,
+    drive_id: &'a DriveId,
     limit: Option<u32>,
     marker: Option<&'a str>,
     /*
@@ -148,26 +255,27 @@ pub struct SearchFilesRequest<'a> {
     size ASC | DESC
     */
     order_by: Option<&'a str>,
-    video_thumbnail_time: Option<u32>,
-    video_thumbnail_width: Option<u32>,
-    image_thumbnail_width: Option<u32>,
+    #[serde(flatten)]
+    thumbnail: ThumbnailFields,
     return_total_count: Option<bool>,
 }
 
 impl<'a> SearchFilesRequest<'a> {
     pub fn new(
-        drive_id: &'a str,
+        drive_id: &'a DriveId,
         query: Option<&'a str>,
         marker: Option<&'a str>,
         order_by: Option<&'a str>,
+        thumbnail: Option<&ThumbnailOptions>,
     ) -> Self {
         Self {
             drive_id,
             query,
             marker,
             order_by,
+            thumbnail: thumbnail.into(),
             return_total_count: Some(true),
-            ..Default::default()
+            limit: None,
         }
     }
 }
@@ -178,27 +286,32 @@ impl Request for SearchFilesRequest<'_> {
     type Response = ListFilesResponse;
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
 pub struct ListStarredFilesRequest<'a> {
-    drive_id: &'a str,
+    drive_id: &'a DriveId,
     limit: Option<u32>,
     marker: Option<&'a str>,
     r#type: Option<FileType>,
     order_by: Option<OrderBy>,
     order_direction: Option<SortBy>,
-    video_thumbnail_time: Option<u32>,
-    video_thumbnail_width: Option<u32>,
-    image_thumbnail_width: Option<u32>,
+    #[serde(flatten)]
+    thumbnail: ThumbnailFields,
 }
 
 impl<'a> ListStarredFilesRequest<'a> {
-    pub fn new(drive_id: &'a str, marker: Option<&'a str>) -> Self {
+    pub fn new(
+        drive_id: &'a DriveId,
+        marker: Option<&'a str>,
+        thumbnail: Option<&ThumbnailOptions>,
+    ) -> Self {
         Self {
             drive_id,
             marker,
+            thumbnail: thumbnail.into(),
             order_by: Some(OrderBy::NameEnhanced),
             order_direction: Some(SortBy::Asc),
-            ..Default::default()
+            limit: None,
+            r#type: None,
         }
     }
 }
@@ -209,22 +322,26 @@ impl Request for ListStarredFilesRequest<'_> {
     type Response = ListFilesResponse;
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
 pub struct GetFileByIdRequest<'a> {
-    drive_id: &'a str,
-    file_id: &'a str,
-    video_thumbnail_time: Option<u32>,
-    video_thumbnail_width: Option<u32>,
-    image_thumbnail_width: Option<u32>,
+    drive_id: &'a DriveId,
+    file_id: &'a FileId,
+    #[serde(flatten)]
+    thumbnail: ThumbnailFields,
     fields: Option<&'a str>, // *
 }
 
 impl<'a> GetFileByIdRequest<'a> {
-    pub fn new(drive_id: &'a str, file_id: &'a str) -> Self {
+    pub fn new(
+        drive_id: &'a DriveId,
+        file_id: &'a FileId,
+        thumbnail: Option<&ThumbnailOptions>,
+    ) -> Self {
         Self {
             drive_id,
             file_id,
-            ..Default::default()
+            thumbnail: thumbnail.into(),
+            fields: None,
         }
     }
 }
@@ -235,14 +352,14 @@ impl Request for GetFileByIdRequest<'_> {
     type Response = FileEntry;
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
 pub struct GetFileByPathRequest<'a> {
-    drive_id: &'a str,
+    drive_id: &'a DriveId,
     file_path: &'a str,
 }
 
 impl<'a> GetFileByPathRequest<'a> {
-    pub fn new(drive_id: &'a str, file_path: &'a str) -> Self {
+    pub fn new(drive_id: &'a DriveId, file_path: &'a str) -> Self {
         Self {
             drive_id,
             file_path,
@@ -259,20 +376,23 @@ impl Request for GetFileByPathRequest<'_> {
 #[derive(Debug, Serialize, Default)]
 pub struct BatchGetFilesRequest<'a> {
     file_list: Vec<GetFileByIdRequest<'a>>,
-    video_thumbnail_time: Option<u32>,
-    video_thumbnail_width: Option<u32>,
-    image_thumbnail_width: Option<u32>,
+    #[serde(flatten)]
+    thumbnail: ThumbnailFields,
 }
 
 impl<'a> BatchGetFilesRequest<'a> {
-    pub fn new(drive_id: &'a str, file_ids: &[&'a str]) -> Self {
+    pub fn new(
+        drive_id: &'a DriveId,
+        file_ids: &'a [FileId],
+        thumbnail: Option<&ThumbnailOptions>,
+    ) -> Self {
         let file_list = file_ids
             .iter()
-            .map(|file_id| GetFileByIdRequest::new(drive_id, file_id))
+            .map(|file_id| GetFileByIdRequest::new(drive_id, file_id, None))
             .collect();
         Self {
             file_list,
-            ..Default::default()
+            thumbnail: thumbnail.into(),
         }
     }
 }
@@ -282,15 +402,15 @@ impl Request for BatchGetFilesRequest<'_> {
     type Response = ListFilesResponse;
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
 pub struct GetDownloadUrlRequest<'a> {
-    drive_id: &'a str,
-    file_id: &'a str,
+    drive_id: &'a DriveId,
+    file_id: &'a FileId,
     expire_sec: Option<u32>, // default 900s
 }
 
 impl<'a> GetDownloadUrlRequest<'a> {
-    pub fn new(drive_id: &'a str, file_id: &'a str) -> Self {
+    pub fn new(drive_id: &'a DriveId, file_id: &'a FileId) -> Self {
         Self {
             drive_id,
             file_id,
@@ -305,7 +425,7 @@ impl Request for GetDownloadUrlRequest<'_> {
     type Response = GetDownloadUrlResponse;
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GetDownloadUrlResponse {
     pub url: String,
     pub expiration: String,
@@ -328,13 +448,13 @@ impl Request for DownloadFileRequest<'_> {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
 pub struct PartInfo {
     pub part_number: u16,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub upload_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub part_size: Option<u32>,
+    pub part_size: Option<u64>,
 }
 
 impl Request for PartInfo {
@@ -359,10 +479,10 @@ pub struct StreamsInfo {
     pub part_info_list: Option<Vec<PartInfo>>,
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
 pub struct CreateFileRequest<'a> {
-    drive_id: &'a str,
-    parent_file_id: &'a str,
+    drive_id: &'a DriveId,
+    parent_file_id: &'a FileId,
     name: &'a str,
     r#type: FileType,
     check_name_mode: IfNameExists,
@@ -380,10 +500,11 @@ pub struct CreateFileRequest<'a> {
 
 impl<'a> CreateFileRequest<'a> {
     pub fn new(
-        drive_id: &'a str,
-        parent_file_id: &'a str,
+        drive_id: &'a DriveId,
+        parent_file_id: &'a FileId,
         name: &'a str,
         r#type: FileType,
+        check_name_mode: Option<IfNameExists>,
         part_info_list: Option<Vec<PartInfo>>,
         pre_hash: Option<&'a str>,          // content_hash needed
         size: Option<u64>,                  // content_hash needed
@@ -391,12 +512,15 @@ impl<'a> CreateFileRequest<'a> {
         proof_version: Option<&'a str>,     // content_hash needed, default v1
         content_hash: Option<&'a str>,      // content_hash needed
         content_hash_name: Option<&'a str>, // content_hash needed, default sha1
+        local_created_at: Option<&'a str>,
+        local_modified_at: Option<&'a str>,
     ) -> Self {
         Self {
             drive_id,
             parent_file_id,
             name,
             r#type,
+            check_name_mode: check_name_mode.unwrap_or_default(),
             part_info_list,
             pre_hash,
             size,
@@ -404,7 +528,9 @@ impl<'a> CreateFileRequest<'a> {
             proof_version,
             content_hash,
             content_hash_name,
-            ..Default::default()
+            local_created_at,
+            local_modified_at,
+            streams_info: None,
         }
     }
 }
@@ -415,15 +541,15 @@ impl Request for CreateFileRequest<'_> {
     type Response = CreateFileResponse;
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum CreateFileResponse {
     FileCreated {
-        drive_id: String,
-        file_id: String,
+        drive_id: DriveId,
+        file_id: FileId,
         status: Option<String>,
-        parent_file_id: String,
-        upload_id: Option<String>,
+        parent_file_id: FileId,
+        upload_id: Option<UploadId>,
         file_name: String,
         available: Option<bool>,
         exist: Option<bool>,
@@ -443,6 +569,13 @@ impl CreateFileResponse {
         }
     }
 
+    pub fn exist(&self) -> bool {
+        match self {
+            CreateFileResponse::FileCreated { exist, .. } => exist.unwrap_or(false),
+            _ => false,
+        }
+    }
+
     pub fn content_hash_matched(&self) -> bool {
         match self {
             CreateFileResponse::FileCreated { rapid_upload, .. } => rapid_upload.unwrap_or(false),
@@ -450,43 +583,48 @@ impl CreateFileResponse {
         }
     }
 
-    pub fn upload_id(&self) -> String {
+    // errors on a PreHashMatched response, no file exists yet then
+    pub fn upload_id(&self) -> crate::Result<UploadId> {
         match self {
-            CreateFileResponse::FileCreated { upload_id, .. } => upload_id.clone().unwrap(),
-            _ => panic!("upload_id not found"),
+            CreateFileResponse::FileCreated {
+                upload_id: Some(upload_id),
+                ..
+            } => Ok(upload_id.clone()),
+            _ => Err(anyhow!("upload_id not found")),
         }
     }
 
-    pub fn file_id(&self) -> String {
+    pub fn file_id(&self) -> crate::Result<FileId> {
         match self {
-            CreateFileResponse::FileCreated { file_id, .. } => file_id.clone(),
-            _ => panic!("file_id not found"),
+            CreateFileResponse::FileCreated { file_id, .. } => Ok(file_id.clone()),
+            _ => Err(anyhow!("file_id not found")),
         }
     }
 
-    pub fn part_info_list(&self) -> Vec<PartInfo> {
+    pub fn part_info_list(&self) -> crate::Result<Vec<PartInfo>> {
         match self {
-            CreateFileResponse::FileCreated { part_info_list, .. } => {
-                part_info_list.clone().unwrap()
-            }
-            _ => panic!("part_info_list not found"),
+            CreateFileResponse::FileCreated {
+                part_info_list: Some(part_info_list),
+                ..
+            } => Ok(part_info_list.clone()),
+            _ => Err(anyhow!("part_info_list not found")),
         }
     }
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
 pub struct FlushUploadUrlRequest<'a> {
-    drive_id: &'a str,
-    file_id: &'a str,
-    upload_id: &'a str,
+    drive_id: &'a DriveId,
+    file_id: &'a FileId,
+    upload_id: &'a UploadId,
     part_info_list: Vec<PartInfo>,
 }
 
 impl<'a> FlushUploadUrlRequest<'a> {
     pub fn new(
-        drive_id: &'a str,
-        file_id: &'a str,
-        upload_id: &'a str,
+        drive_id: &'a DriveId,
+        file_id: &'a FileId,
+        upload_id: &'a UploadId,
         part_number_list: &[u16],
     ) -> Self {
         Self {
@@ -510,28 +648,28 @@ impl Request for FlushUploadUrlRequest<'_> {
     type Response = FlushUploadUrlResponse;
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FlushUploadUrlResponse {
-    pub drive_id: String,
-    pub file_id: String,
-    pub upload_id: String,
+    pub drive_id: DriveId,
+    pub file_id: FileId,
+    pub upload_id: UploadId,
     pub create_at: String,
     pub part_info_list: Vec<PartInfo>,
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
 pub struct ListUploadedPartsRequest<'a> {
-    drive_id: &'a str,
-    file_id: &'a str,
-    upload_id: &'a str,
+    drive_id: &'a DriveId,
+    file_id: &'a FileId,
+    upload_id: &'a UploadId,
     part_number_marker: Option<String>,
 }
 
 impl<'a> ListUploadedPartsRequest<'a> {
     pub fn new(
-        drive_id: &'a str,
-        file_id: &'a str,
-        upload_id: &'a str,
+        drive_id: &'a DriveId,
+        file_id: &'a FileId,
+        upload_id: &'a UploadId,
         part_number_marker: Option<String>,
     ) -> Self {
         Self {
@@ -549,32 +687,32 @@ impl Request for ListUploadedPartsRequest<'_> {
     type Response = ListUploadedPartsResponse;
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UploadedParts {
     pub etag: String,
     pub part_number: u16,
-    pub part_size: u32,
+    pub part_size: u64,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ListUploadedPartsResponse {
-    pub file_id: String,
-    pub upload_id: String,
+    pub file_id: FileId,
+    pub upload_id: UploadId,
     #[serde(rename = "parallelUpload")]
     pub parallel_upload: bool,
     pub uploaded_parts: Vec<UploadedParts>,
     pub next_part_number_marker: String,
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
 pub struct CompleteUploadRequest<'a> {
-    drive_id: &'a str,
-    file_id: &'a str,
-    upload_id: &'a str,
+    drive_id: &'a DriveId,
+    file_id: &'a FileId,
+    upload_id: &'a UploadId,
 }
 
 impl<'a> CompleteUploadRequest<'a> {
-    pub fn new(drive_id: &'a str, file_id: &'a str, upload_id: &'a str) -> Self {
+    pub fn new(drive_id: &'a DriveId, file_id: &'a FileId, upload_id: &'a UploadId) -> Self {
         Self {
             drive_id,
             file_id,
@@ -589,10 +727,10 @@ impl Request for CompleteUploadRequest<'_> {
     type Response = FileEntry;
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
 pub struct UpdateFileRequest<'a> {
-    drive_id: &'a str,
-    file_id: &'a str,
+    drive_id: &'a DriveId,
+    file_id: &'a FileId,
     name: Option<&'a str>,
     check_name_mode: Option<IfNameExists>,
     starred: Option<bool>,
@@ -600,8 +738,8 @@ pub struct UpdateFileRequest<'a> {
 
 impl<'a> UpdateFileRequest<'a> {
     pub fn new(
-        drive_id: &'a str,
-        file_id: &'a str,
+        drive_id: &'a DriveId,
+        file_id: &'a FileId,
         name: Option<&'a str>,
         check_name_mode: Option<IfNameExists>,
         starred: Option<bool>,
@@ -622,27 +760,28 @@ impl Request for UpdateFileRequest<'_> {
     type Response = FileEntry;
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
 pub struct MoveFileRequest<'a> {
-    drive_id: &'a str,
-    file_id: &'a str,
-    to_parent_file_id: &'a str,
+    drive_id: &'a DriveId,
+    file_id: &'a FileId,
+    to_parent_file_id: &'a FileId,
     check_name_mode: Option<IfNameExists>,
     new_name: Option<&'a str>,
 }
 
 impl<'a> MoveFileRequest<'a> {
     pub fn new(
-        drive_id: &'a str,
-        file_id: &'a str,
-        to_parent_file_id: &'a str,
+        drive_id: &'a DriveId,
+        file_id: &'a FileId,
+        to_parent_file_id: &'a FileId,
         rename: Option<&'a str>,
+        check_name_mode: Option<IfNameExists>,
     ) -> Self {
         Self {
             drive_id,
             file_id,
             to_parent_file_id,
-            check_name_mode: Some(IfNameExists::AutoRename),
+            check_name_mode: Some(check_name_mode.unwrap_or_default()),
             new_name: rename,
         }
     }
@@ -654,31 +793,36 @@ impl Request for MoveFileRequest<'_> {
     type Response = AsyncTaskResponse;
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AsyncTaskResponse {
-    pub drive_id: String,
-    pub file_id: String,
-    pub async_task_id: Option<String>,
+    pub drive_id: DriveId,
+    pub file_id: FileId,
+    pub async_task_id: Option<TaskId>,
     pub exit: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
 pub struct CopyFileRequest<'a> {
-    drive_id: &'a str,
-    file_id: &'a str,
-    to_drive_id: Option<&'a str>,
-    to_parent_file_id: &'a str,
+    drive_id: &'a DriveId,
+    file_id: &'a FileId,
+    to_drive_id: Option<&'a DriveId>,
+    to_parent_file_id: &'a FileId,
     auto_rename: Option<bool>,
 }
 
 impl<'a> CopyFileRequest<'a> {
-    pub fn new(drive_id: &'a str, file_id: &'a str, to_parent_file_id: &'a str) -> Self {
+    pub fn new(
+        drive_id: &'a DriveId,
+        file_id: &'a FileId,
+        to_parent_file_id: &'a FileId,
+        auto_rename: bool,
+    ) -> Self {
         Self {
             drive_id,
             file_id,
             to_parent_file_id,
             to_drive_id: Some(drive_id),
-            auto_rename: Some(true),
+            auto_rename: Some(auto_rename),
         }
     }
 }
@@ -689,14 +833,14 @@ impl Request for CopyFileRequest<'_> {
     type Response = AsyncTaskResponse;
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
 pub struct RecycleFileRequest<'a> {
-    drive_id: &'a str,
-    file_id: &'a str,
+    drive_id: &'a DriveId,
+    file_id: &'a FileId,
 }
 
 impl<'a> RecycleFileRequest<'a> {
-    pub fn new(drive_id: &'a str, file_id: &'a str) -> Self {
+    pub fn new(drive_id: &'a DriveId, file_id: &'a FileId) -> Self {
         Self { drive_id, file_id }
     }
 }
@@ -707,10 +851,62 @@ impl Request for RecycleFileRequest<'_> {
     type Response = AsyncTaskResponse;
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
+pub struct ListRecycleBinRequest<'a> {
+    drive_id: &'a DriveId,
+    limit: Option<u32>, // 1..=200
+    marker: Option<&'a str>,
+}
+
+impl<'a> ListRecycleBinRequest<'a> {
+    pub fn new(drive_id: &'a DriveId, marker: Option<&'a str>, limit: Option<u32>) -> Self {
+        Self {
+            drive_id,
+            limit,
+            marker,
+        }
+    }
+}
+
+impl Request for ListRecycleBinRequest<'_> {
+    const URI: &'static str = "/adrive/v1.0/openFile/recyclebin/list";
+    const METHOD: reqwest::Method = Method::POST;
+    type Response = ListFilesResponse;
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestoreFileRequest<'a> {
+    pub drive_id: &'a DriveId,
+    pub file_id: &'a FileId,
+}
+
+impl Request for RestoreFileRequest<'_> {
+    const URI: &'static str = "/adrive/v1.0/openFile/recyclebin/restore";
+    const METHOD: reqwest::Method = Method::POST;
+    type Response = AsyncTaskResponse;
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClearRecycleBinRequest<'a> {
+    pub drive_id: &'a DriveId,
+}
+
+impl Request for ClearRecycleBinRequest<'_> {
+    const URI: &'static str = "/adrive/v1.0/openFile/recyclebin/clear";
+    const METHOD: reqwest::Method = Method::POST;
+    type Response = ClearRecycleBinResponse;
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClearRecycleBinResponse {
+    pub drive_id: DriveId,
+    pub async_task_id: Option<TaskId>,
+}
+
+#[derive(Debug, Serialize)]
 pub struct DeleteFileRequest<'a> {
-    pub drive_id: &'a str,
-    pub file_id: &'a str,
+    pub drive_id: &'a DriveId,
+    pub file_id: &'a FileId,
 }
 
 impl Request for DeleteFileRequest<'_> {
@@ -719,9 +915,9 @@ impl Request for DeleteFileRequest<'_> {
     type Response = AsyncTaskResponse;
 }
 
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize)]
 pub struct GetAsyncTaskStateRequest<'a> {
-    pub async_task_id: &'a str,
+    pub async_task_id: &'a TaskId,
 }
 
 impl Request for GetAsyncTaskStateRequest<'_> {
@@ -730,11 +926,25 @@ impl Request for GetAsyncTaskStateRequest<'_> {
     type Response = GetAsyncTaskStateResponse;
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum AsyncTaskState {
     Succeed,
     Running,
     Failed,
+    // a state this client does not know yet
+    Unknown(String),
+}
+
+impl AsyncTaskState {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AsyncTaskState::Succeed => "Succeed",
+            AsyncTaskState::Running => "Running",
+            AsyncTaskState::Failed => "Failed",
+            AsyncTaskState::Unknown(s) => s,
+        }
+    }
 }
 
 impl From<String> for AsyncTaskState {
@@ -743,13 +953,199 @@ impl From<String> for AsyncTaskState {
             "Succeed" => AsyncTaskState::Succeed,
             "Running" => AsyncTaskState::Running,
             "Failed" => AsyncTaskState::Failed,
-            _ => panic!("invalid async task state"),
+            _ => AsyncTaskState::Unknown(s),
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+impl Serialize for AsyncTaskState {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AsyncTaskState {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(String::deserialize(deserializer)?.into())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GetAsyncTaskStateResponse {
     pub state: AsyncTaskState,
-    pub async_task_id: String,
+    pub async_task_id: TaskId,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folder_fixture() {
+        let entry: FileEntry =
+            serde_json::from_str(include_str!("../../tests/fixtures/folder.json")).unwrap();
+        assert!(entry.is_dir());
+        assert!(entry.parent_file_id.is_root());
+        assert_eq!(entry.size, None);
+        assert_eq!(entry.content_hash, None);
+        assert_eq!(entry.category, None);
+        assert_eq!(
+            entry.updated_at,
+            "2024-01-21T02:01:06.221Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(entry.local_created_at, None);
+    }
+
+    #[test]
+    fn file_times_and_categories() {
+        let entry: FileEntry =
+            serde_json::from_str(include_str!("../../tests/fixtures/video_file.json")).unwrap();
+        assert!(!entry.is_dir());
+        assert_eq!(entry.category, Some(Category::Video));
+        assert!(entry.created_at < entry.updated_at);
+        assert_eq!(
+            entry.local_modified_at,
+            Some("2023-05-01T10:22:37Z".parse().unwrap())
+        );
+
+        let category: Category = serde_json::from_str(r#""ebook""#).unwrap();
+        assert_eq!(category, Category::Unknown("ebook".to_string()));
+    }
+
+    // fields of the responses FileEntry doesn't model
+    const UNMODELED: &[&str] = &[
+        ".domain_id",
+        ".hidden",
+        ".starred",
+        ".status",
+        ".user_meta",
+        ".encrypt_mode",
+        ".creator_type",
+        ".creator_id",
+        ".last_modifier_type",
+        ".last_modifier_id",
+        ".revision_id",
+        ".revision_version",
+        ".sync_flag",
+        ".sync_device_flag",
+        ".sync_meta",
+        ".trashed",
+        ".punish_flag",
+        ".ex_fields_info",
+        ".content_type",
+        ".mime_type",
+        ".mime_extension",
+        ".labels",
+        ".upload_id",
+        ".content_hash_name",
+        ".image_media_metadata.image_quality",
+    ];
+
+    fn wire_number(s: &str) -> Option<f64> {
+        match s.split_once('/') {
+            Some((n, d)) => Some(n.parse::<f64>().ok()? / d.parse::<f64>().ok()?),
+            None => s.parse().ok(),
+        }
+    }
+
+    // the server sends numbers as strings, times with millis and empty
+    // strings for missing values, everything else has to match exactly
+    fn assert_wire_eq(ours: &serde_json::Value, wire: &serde_json::Value, path: &str) {
+        use serde_json::Value;
+        match (ours, wire) {
+            (Value::Object(ours), Value::Object(wire)) => {
+                for (key, wire) in wire {
+                    let path = format!("{}.{}", path, key);
+                    if UNMODELED.contains(&path.as_str()) {
+                        continue;
+                    }
+                    let ours = ours
+                        .get(key)
+                        .unwrap_or_else(|| panic!("{} is not serialized", path));
+                    assert_wire_eq(ours, wire, &path);
+                }
+                for (key, ours) in ours {
+                    let missing = ours.is_null() || ours.as_array().is_some_and(Vec::is_empty);
+                    assert!(
+                        wire.contains_key(key) || missing,
+                        "{}.{} is not in the response",
+                        path,
+                        key
+                    );
+                }
+            }
+            (Value::Array(ours), Value::Array(wire)) => {
+                assert_eq!(ours.len(), wire.len(), "{}", path);
+                for (i, (ours, wire)) in ours.iter().zip(wire).enumerate() {
+                    assert_wire_eq(ours, wire, &format!("{}[{}]", path, i));
+                }
+            }
+            (Value::Null, Value::String(wire)) if wire.is_empty() => {}
+            (Value::Number(ours), Value::String(wire)) => {
+                let wire = wire_number(wire).unwrap_or_else(|| panic!("{} is {}", path, wire));
+                assert!((ours.as_f64().unwrap() - wire).abs() < 1e-9, "{}", path);
+            }
+            (Value::String(ours), Value::String(wire)) if ours != wire => {
+                let time = |s: &str| s.parse::<DateTime<Utc>>().ok();
+                assert!(time(ours).is_some() && time(ours) == time(wire), "{}", path);
+            }
+            _ => assert_eq!(ours, wire, "{}", path),
+        }
+    }
+
+    #[test]
+    fn entries_match_the_responses() {
+        for fixture in [
+            include_str!("../../tests/fixtures/folder.json"),
+            include_str!("../../tests/fixtures/video_file.json"),
+            include_str!("../../tests/fixtures/image_file.json"),
+        ] {
+            let wire: serde_json::Value = serde_json::from_str(fixture).unwrap();
+            let entry: FileEntry = serde_json::from_value(wire.clone()).unwrap();
+            let ours = serde_json::to_value(&entry).unwrap();
+            assert_wire_eq(&ours, &wire, "");
+            assert_eq!(serde_json::from_value::<FileEntry>(ours).unwrap(), entry);
+        }
+    }
+
+    #[test]
+    fn thumbnail_fields_are_flattened() {
+        let (drive_id, file_id) = (DriveId::from("1"), FileId::from("f1"));
+        let options = ThumbnailOptions {
+            width: Some(480),
+            video_time: Some(Duration::from_millis(1500)),
+        };
+        let req = GetFileByIdRequest::new(&drive_id, &file_id, Some(&options));
+        let body = serde_json::to_value(req).unwrap();
+        assert_eq!(body["video_thumbnail_time"], 1500);
+        assert_eq!(body["video_thumbnail_width"], 480);
+        assert_eq!(body["image_thumbnail_width"], 480);
+        assert!(body.get("thumbnail").is_none());
+    }
+
+    #[test]
+    fn unknown_async_task_state() {
+        let resp: GetAsyncTaskStateResponse =
+            serde_json::from_str(r#"{"state": "Failed", "async_task_id": "t1"}"#).unwrap();
+        assert_eq!(resp.state, AsyncTaskState::Failed);
+
+        let resp: GetAsyncTaskStateResponse =
+            serde_json::from_str(r#"{"state": "PartialSucceed", "async_task_id": "t1"}"#).unwrap();
+        assert_eq!(
+            resp.state,
+            AsyncTaskState::Unknown("PartialSucceed".to_string())
+        );
+        assert_eq!(serde_json::to_value(&resp.state).unwrap(), "PartialSucceed");
+    }
+
+    #[test]
+    fn pre_hash_matched_has_no_file() {
+        let resp: CreateFileResponse =
+            serde_json::from_str(r#"{"code": "PreHashMatched", "message": "Pre hash matched."}"#)
+                .unwrap();
+        assert!(resp.pre_hash_matched());
+        assert!(resp.file_id().is_err());
+        assert!(resp.upload_id().is_err());
+        assert!(resp.part_info_list().is_err());
+    }
 }
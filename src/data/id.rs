@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+
+// the ids are plain strings on the wire, the types only keep them apart
+macro_rules! id {
+    ($name:ident) => {
+        #[derive(
+            Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name(Cow<'static, str>);
+
+        impl $name {
+            pub fn new(id: impl Into<String>) -> Self {
+                Self(Cow::Owned(id.into()))
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                Self::new(id)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                Self::new(id)
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+    };
+}
+
+id!(DriveId);
+id!(FileId);
+id!(UploadId);
+id!(TaskId);
+
+impl FileId {
+    // the top folder of every drive
+    pub const ROOT: FileId = FileId(Cow::Borrowed("root"));
+
+    pub fn is_root(&self) -> bool {
+        self == &Self::ROOT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_plain_strings_on_the_wire() {
+        let file_id: FileId = serde_json::from_str(r#""root""#).unwrap();
+        assert_eq!(file_id, FileId::ROOT);
+        assert!(file_id.is_root());
+        assert_eq!(
+            serde_json::to_string(&DriveId::from("1")).unwrap(),
+            r#""1""#
+        );
+        assert_eq!(TaskId::new("t1").to_string(), "t1");
+        assert_eq!(UploadId::from("u1"), "u1");
+    }
+}
@@ -0,0 +1,225 @@
+use super::file::optional_time;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+// the server sends most numbers as strings, sometimes empty ones
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Number {
+    Float(f64),
+    Text(String),
+}
+
+impl Number {
+    fn to_f64(&self) -> Option<f64> {
+        match self {
+            Number::Float(n) => Some(*n),
+            // frame rates look like 30000/1001
+            Number::Text(s) => match s.trim().split_once('/') {
+                Some((n, d)) => {
+                    let d: f64 = d.trim().parse().ok()?;
+                    (d != 0.0).then_some(n.trim().parse::<f64>().ok()? / d)
+                }
+                None => s.trim().parse().ok(),
+            },
+        }
+    }
+}
+
+fn number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    let number = Option::<Number>::deserialize(deserializer)?;
+    Ok(number.as_ref().and_then(Number::to_f64))
+}
+
+fn integer<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    Ok(number(deserializer)?
+        .filter(|n| *n >= 0.0)
+        .map(|n| n as u64))
+}
+
+// durations go over the wire as fractional seconds
+pub(crate) mod seconds {
+    use serde::{Deserializer, Serialize, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        duration.map(|d| d.as_secs_f64()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Ok(super::number(deserializer)?
+            .filter(|n| n.is_finite() && *n >= 0.0)
+            .map(Duration::from_secs_f64))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VideoMediaMetadata {
+    #[serde(default)]
+    pub width: u32,
+    #[serde(default)]
+    pub height: u32,
+    #[serde(default, with = "seconds")]
+    pub duration: Option<Duration>,
+    #[serde(default)]
+    pub video_media_video_stream: Vec<VideoStream>,
+    #[serde(default)]
+    pub video_media_audio_stream: Vec<AudioStream>,
+    // when the video was shot
+    #[serde(default, deserialize_with = "optional_time")]
+    pub time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VideoStream {
+    pub code_name: Option<String>,
+    #[serde(default, deserialize_with = "integer")]
+    pub bitrate: Option<u64>,
+    #[serde(default, deserialize_with = "number")]
+    pub fps: Option<f64>,
+    #[serde(default, with = "seconds")]
+    pub duration: Option<Duration>,
+    pub clarity: Option<String>,
+    #[serde(default, deserialize_with = "integer")]
+    pub frame_count: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioStream {
+    pub code_name: Option<String>,
+    #[serde(default, alias = "bitrate", deserialize_with = "integer")]
+    pub bit_rate: Option<u64>,
+    #[serde(default, deserialize_with = "integer")]
+    pub channels: Option<u64>,
+    pub channel_layout: Option<String>,
+    #[serde(default, deserialize_with = "integer")]
+    pub sample_rate: Option<u64>,
+    #[serde(default, with = "seconds")]
+    pub duration: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VideoPreviewMetadata {
+    #[serde(default)]
+    pub width: u32,
+    #[serde(default)]
+    pub height: u32,
+    #[serde(default, with = "seconds")]
+    pub duration: Option<Duration>,
+    #[serde(default, deserialize_with = "integer")]
+    pub bitrate: Option<u64>,
+    #[serde(default, deserialize_with = "number")]
+    pub frame_rate: Option<f64>,
+    pub video_format: Option<String>,
+    pub audio_format: Option<String>,
+    #[serde(default)]
+    pub template_list: Vec<PreviewTemplate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreviewTemplate {
+    pub template_id: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageMediaMetadata {
+    #[serde(default)]
+    pub width: u32,
+    #[serde(default)]
+    pub height: u32,
+    // when the photo was taken
+    #[serde(default, deserialize_with = "optional_time")]
+    pub time: Option<DateTime<Utc>>,
+    // "latitude,longitude"
+    pub location: Option<String>,
+    // a json object encoded as a string, see exif_fields
+    pub exif: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ExifField {
+    value: String,
+}
+
+impl ImageMediaMetadata {
+    // the exif tags like Model or DateTimeOriginal with their values
+    pub fn exif_fields(&self) -> crate::Result<HashMap<String, String>> {
+        let Some(exif) = self.exif.as_deref().filter(|exif| !exif.is_empty()) else {
+            return Ok(HashMap::new());
+        };
+        let fields: HashMap<String, ExifField> = serde_json::from_str(exif)?;
+        Ok(fields.into_iter().map(|(k, v)| (k, v.value)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ImageMediaMetadata;
+    use crate::data::FileEntry;
+    use chrono::{DateTime, TimeZone, Utc};
+    use std::time::Duration;
+
+    fn shot_at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 5, 1, 10, 20, 30).unwrap()
+    }
+
+    #[test]
+    fn video_fixture() {
+        let entry: FileEntry =
+            serde_json::from_str(include_str!("../../tests/fixtures/video_file.json")).unwrap();
+        let metadata = entry.video_media_metadata.unwrap();
+        assert_eq!((metadata.width, metadata.height), (1920, 1080));
+        assert_eq!(metadata.duration, Some(Duration::from_millis(126_968)));
+        assert_eq!(metadata.time, Some(shot_at()));
+
+        let video = &metadata.video_media_video_stream[0];
+        assert_eq!(video.code_name.as_deref(), Some("h264"));
+        assert_eq!(video.bitrate, Some(7_654_321));
+        assert!((video.fps.unwrap() - 29.97).abs() < 0.01);
+        assert_eq!(video.frame_count, Some(3805));
+
+        let audio = &metadata.video_media_audio_stream[0];
+        assert_eq!(audio.code_name.as_deref(), Some("aac"));
+        assert_eq!(audio.channels, Some(2));
+        assert_eq!(audio.bit_rate, Some(128_000));
+        assert_eq!(audio.sample_rate, Some(44_100));
+
+        let preview = entry.video_preview_metadata.unwrap();
+        assert_eq!(preview.duration, Some(Duration::from_millis(126_968)));
+        assert_eq!(preview.frame_rate, Some(30.0));
+        assert_eq!(preview.template_list.len(), 3);
+        assert!(entry.image_media_metadata.is_none());
+        assert_eq!(entry.play_cursor, Some(Duration::from_millis(35_541)));
+    }
+
+    #[test]
+    fn image_fixture() {
+        let entry: FileEntry =
+            serde_json::from_str(include_str!("../../tests/fixtures/image_file.json")).unwrap();
+        let metadata = entry.image_media_metadata.unwrap();
+        assert_eq!((metadata.width, metadata.height), (4032, 3024));
+        assert_eq!(metadata.time, Some(shot_at()));
+        let exif = metadata.exif_fields().unwrap();
+        assert_eq!(exif["Model"], "iPhone 12");
+        assert_eq!(exif["DateTimeOriginal"], "2023:05:01 10:20:30");
+        assert!(entry.video_media_metadata.is_none());
+    }
+
+    #[test]
+    fn empty_time() {
+        let metadata: ImageMediaMetadata =
+            serde_json::from_str(r#"{"width": 1, "height": 1, "time": ""}"#).unwrap();
+        assert_eq!(metadata.time, None);
+        let metadata: ImageMediaMetadata =
+            serde_json::from_str(r#"{"width": 1, "height": 1}"#).unwrap();
+        assert_eq!(metadata.time, None);
+    }
+}
@@ -1,17 +1,46 @@
 mod auth;
 mod error;
 mod file;
+mod id;
+mod media;
 mod user;
+mod video;
+use crate::throttle::{throttled_body, BandwidthLimit};
 use crate::{constants, Result};
 use async_trait::async_trait;
 pub(crate) use auth::*;
+pub use auth::{
+    GetAccessTokenResponse, GetQRCodeImageResponse, GetQRCodeResponse, GetQRCodeStatusResponse,
+    QRCodeStatus,
+};
+pub use error::{ErrorResponse, InsufficientSpaceError};
 pub(crate) use file::*;
+pub use file::{
+    AsyncTaskResponse, AsyncTaskState, Category, ClearRecycleBinResponse, CreateFileResponse,
+    FileEntry, FileType, FlushUploadUrlResponse, GetAsyncTaskStateResponse, GetDownloadUrlResponse,
+    IfNameExists, ListFilesResponse, ListUploadedPartsResponse, PartInfo, ThumbnailOptions,
+    UploadedParts,
+};
+pub use id::{DriveId, FileId, TaskId, UploadId};
+pub use media::{
+    AudioStream, ImageMediaMetadata, PreviewTemplate, VideoMediaMetadata, VideoPreviewMetadata,
+    VideoStream,
+};
+use reqwest::header::{HeaderMap, CONTENT_LENGTH};
 use reqwest::StatusCode;
-use reqwest::{header::HeaderMap, Client, Method, Url};
+use reqwest::{Client, Method, Url};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::sync::OnceLock;
 pub(crate) use user::*;
+pub use user::{
+    GetDriveInfoResponse, GetSpaceInfoResponse, GetUserInfoResponse, PersonalSpaceInfo,
+};
+pub(crate) use video::*;
+pub use video::{
+    GetVideoPreviewPlayInfoResponse, LiveTranscodingSubtitleTask, LiveTranscodingTask,
+    UpdateVideoRecordResponse, VideoPreviewPlayInfo, VideoQuality,
+};
 
 pub(crate) static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
 
@@ -93,12 +122,12 @@ pub(crate) trait Request: Sized + Serialize {
         token: Option<&str>,
     ) -> Result<reqwest::Response> {
         let path = self.path_join()?;
-        let resp = Self::reqwest_client()
-            .get(path)
-            .bearer_auth(token.unwrap_or_default())
-            .headers(headers.unwrap_or_default())
-            .send()
-            .await?;
+        let mut req = Self::reqwest_client().get(path);
+        // without a token nothing is sent, signed urls on a cdn don't need one
+        if let Some(token) = token {
+            req = req.bearer_auth(token);
+        }
+        let resp = req.headers(headers.unwrap_or_default()).send().await?;
         Ok(resp)
     }
 
@@ -107,13 +136,22 @@ pub(crate) trait Request: Sized + Serialize {
         headers: Option<HeaderMap>,
         token: Option<&str>,
         data: Vec<u8>,
+        limits: Vec<BandwidthLimit>,
     ) -> Result<reqwest::Response> {
         let path = self.path_join()?;
+        let mut headers = headers.unwrap_or_default();
+        let body = if limits.iter().any(|limit| limit.get().is_some()) {
+            // a streamed body is sent chunked unless the length is given
+            headers.insert(CONTENT_LENGTH, data.len().into());
+            reqwest::Body::wrap_stream(throttled_body(data.into(), limits))
+        } else {
+            reqwest::Body::from(data)
+        };
         let resp = Self::reqwest_client()
             .put(path)
-            .body(data)
+            .body(body)
             .bearer_auth(token.unwrap_or_default())
-            .headers(headers.unwrap_or_default())
+            .headers(headers)
             .send()
             .await?;
         Ok(resp)
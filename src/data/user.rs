@@ -1,4 +1,4 @@
-use crate::data::Request;
+use crate::data::{DriveId, Request};
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
@@ -10,7 +10,7 @@ impl Request for GetUserInfoRequest {
     const METHOD: reqwest::Method = Method::GET;
     type Response = GetUserInfoResponse;
 }
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GetUserInfoResponse {
     pub id: String,
     pub name: String,
@@ -26,14 +26,14 @@ impl Request for GetDriveInfoRequest {
     const METHOD: reqwest::Method = Method::POST;
     type Response = GetDriveInfoResponse;
 }
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GetDriveInfoResponse {
     pub user_id: String,
     pub name: String,
     pub avatar: String,
-    pub default_drive_id: String,
-    pub resource_drive_id: Option<String>,
-    pub backup_drive_id: Option<String>,
+    pub default_drive_id: DriveId,
+    pub resource_drive_id: Option<DriveId>,
+    pub backup_drive_id: Option<DriveId>,
 }
 
 #[derive(Debug, Serialize)]
@@ -45,13 +45,13 @@ impl Request for GetSpaceInfoRequest {
     type Response = GetSpaceInfoResponse;
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PersonalSpaceInfo {
     pub used_size: u64,
     pub total_size: u64,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GetSpaceInfoResponse {
     pub personal_space_info: PersonalSpaceInfo,
 }
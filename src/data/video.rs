@@ -0,0 +1,262 @@
+use crate::data::media::seconds;
+use crate::data::{DriveId, FileId, ListFilesResponse, Request};
+use chrono::{DateTime, Utc};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Serialize)]
+pub struct GetVideoPreviewPlayInfoRequest<'a> {
+    drive_id: &'a DriveId,
+    file_id: &'a FileId,
+    category: &'a str,
+    get_subtitle_info: bool,
+    template_id: &'a str,        // empty for all qualities
+    url_expire_sec: Option<u64>, // 900 by default, 14400 at most
+}
+
+impl<'a> GetVideoPreviewPlayInfoRequest<'a> {
+    pub const DEFAULT_URL_EXPIRE_SEC: u64 = 900;
+
+    pub fn new(drive_id: &'a DriveId, file_id: &'a FileId, url_expire_sec: Option<u64>) -> Self {
+        Self {
+            drive_id,
+            file_id,
+            category: "live_transcoding",
+            get_subtitle_info: true,
+            template_id: "",
+            url_expire_sec,
+        }
+    }
+}
+
+impl Request for GetVideoPreviewPlayInfoRequest<'_> {
+    const URI: &'static str = "/adrive/v1.0/openFile/getVideoPreviewPlayInfo";
+    const METHOD: reqwest::Method = Method::POST;
+    type Response = GetVideoPreviewPlayInfoResponse;
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetVideoPreviewPlayInfoResponse {
+    pub drive_id: DriveId,
+    pub file_id: FileId,
+    pub video_preview_play_info: VideoPreviewPlayInfo,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VideoPreviewPlayInfo {
+    pub category: String,
+    #[serde(default)]
+    pub live_transcoding_task_list: Vec<LiveTranscodingTask>,
+    #[serde(default)]
+    pub live_transcoding_subtitle_task_list: Vec<LiveTranscodingSubtitleTask>,
+    // not sent by the server, the urls stop working after this
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl VideoPreviewPlayInfo {
+    // the finished variant of the given quality
+    pub fn variant(&self, quality: &VideoQuality) -> Option<&LiveTranscodingTask> {
+        self.playable().find(|task| &task.template_id == quality)
+    }
+
+    // the finished variant with the highest resolution
+    pub fn best(&self) -> Option<&LiveTranscodingTask> {
+        self.playable().max_by_key(|task| task.template_height)
+    }
+
+    fn playable(&self) -> impl Iterator<Item = &LiveTranscodingTask> {
+        self.live_transcoding_task_list
+            .iter()
+            .filter(|task| task.status == "finished" && task.url.is_some())
+    }
+}
+
+// named after the template ids the server uses
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum VideoQuality {
+    LD,
+    SD,
+    HD,
+    FHD,
+    QHD,
+    Unknown(String),
+}
+
+impl VideoQuality {
+    pub fn as_str(&self) -> &str {
+        match self {
+            VideoQuality::LD => "LD",
+            VideoQuality::SD => "SD",
+            VideoQuality::HD => "HD",
+            VideoQuality::FHD => "FHD",
+            VideoQuality::QHD => "QHD",
+            VideoQuality::Unknown(s) => s,
+        }
+    }
+}
+
+impl From<String> for VideoQuality {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "LD" => VideoQuality::LD,
+            "SD" => VideoQuality::SD,
+            "HD" => VideoQuality::HD,
+            "FHD" => VideoQuality::FHD,
+            "QHD" => VideoQuality::QHD,
+            _ => VideoQuality::Unknown(s),
+        }
+    }
+}
+
+impl Serialize for VideoQuality {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for VideoQuality {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(String::deserialize(deserializer)?.into())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveTranscodingTask {
+    pub template_id: VideoQuality,
+    #[serde(default)]
+    pub template_name: String,
+    #[serde(default)]
+    pub template_width: u32,
+    #[serde(default)]
+    pub template_height: u32,
+    // "finished" once the url can be played
+    pub status: String,
+    // an m3u8 playlist
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveTranscodingSubtitleTask {
+    pub language: String,
+    pub status: String,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpdateVideoRecordRequest<'a> {
+    drive_id: &'a DriveId,
+    file_id: &'a FileId,
+    play_cursor: String, // seconds
+    duration: Option<String>,
+}
+
+impl<'a> UpdateVideoRecordRequest<'a> {
+    pub fn new(
+        drive_id: &'a DriveId,
+        file_id: &'a FileId,
+        play_cursor: Duration,
+        duration: Option<Duration>,
+    ) -> Self {
+        Self {
+            drive_id,
+            file_id,
+            play_cursor: format!("{:.3}", play_cursor.as_secs_f64()),
+            duration: duration.map(|d| format!("{:.3}", d.as_secs_f64())),
+        }
+    }
+}
+
+impl Request for UpdateVideoRecordRequest<'_> {
+    const URI: &'static str = "/adrive/v1.0/openFile/video/updateRecord";
+    const METHOD: reqwest::Method = Method::POST;
+    type Response = UpdateVideoRecordResponse;
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateVideoRecordResponse {
+    pub drive_id: DriveId,
+    pub file_id: FileId,
+    #[serde(default, with = "seconds")]
+    pub play_cursor: Option<Duration>,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct ListRecentVideosRequest {
+    video_thumbnail_width: Option<u32>,
+}
+
+impl Request for ListRecentVideosRequest {
+    const URI: &'static str = "/adrive/v1.1/openFile/video/recentList";
+    const METHOD: reqwest::Method = Method::POST;
+    type Response = ListFilesResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn play_info_variants() {
+        let resp: GetVideoPreviewPlayInfoResponse = serde_json::from_str(
+            r#"{
+                "domain_id": "bj29",
+                "drive_id": "1",
+                "file_id": "65ab71583bc7891eebd6491b8c1d67b0c450c306",
+                "video_preview_play_info": {
+                    "category": "live_transcoding",
+                    "meta": {"duration": 12.5, "width": 1920, "height": 1080},
+                    "live_transcoding_task_list": [
+                        {"template_id": "LD", "template_width": 640, "template_height": 360,
+                         "status": "finished", "url": "https://cn-beijing-data.aliyundrive.net/ld.m3u8"},
+                        {"template_id": "FHD", "template_width": 1920, "template_height": 1080,
+                         "status": "running"},
+                        {"template_id": "HD", "template_width": 1280, "template_height": 720,
+                         "status": "finished", "url": "https://cn-beijing-data.aliyundrive.net/hd.m3u8"},
+                        {"template_id": "UHD", "status": "failed"}
+                    ],
+                    "live_transcoding_subtitle_task_list": [
+                        {"language": "chi", "status": "finished", "url": "https://cn-beijing-data.aliyundrive.net/chi.vtt"}
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+        let info = &resp.video_preview_play_info;
+        assert_eq!(info.best().unwrap().template_id, VideoQuality::HD);
+        assert!(info.variant(&VideoQuality::FHD).is_none());
+        assert_eq!(
+            info.variant(&VideoQuality::LD).unwrap().url.as_deref(),
+            Some("https://cn-beijing-data.aliyundrive.net/ld.m3u8")
+        );
+        assert_eq!(
+            info.live_transcoding_task_list[3].template_id,
+            VideoQuality::Unknown("UHD".to_string())
+        );
+        assert_eq!(info.live_transcoding_subtitle_task_list[0].language, "chi");
+
+        let json = serde_json::to_value(&resp).unwrap();
+        assert_eq!(
+            json["video_preview_play_info"]["live_transcoding_task_list"][3]["template_id"],
+            "UHD"
+        );
+        let copy: GetVideoPreviewPlayInfoResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(copy, resp);
+    }
+
+    #[test]
+    fn play_cursor_is_sent_in_seconds() {
+        let (drive_id, file_id) = (DriveId::from("1"), FileId::from("2"));
+        let req = UpdateVideoRecordRequest::new(
+            &drive_id,
+            &file_id,
+            Duration::from_millis(35_541),
+            Some(Duration::from_secs(127)),
+        );
+        let body = serde_json::to_value(req).unwrap();
+        assert_eq!(body["play_cursor"], "35.541");
+        assert_eq!(body["duration"], "127.000");
+    }
+}
@@ -5,21 +5,51 @@ mod auth;
 mod constants;
 mod core;
 mod data;
+mod manager;
+mod progress;
 mod self_hosting;
+mod throttle;
+mod transfer;
 mod utils;
 
 use anyhow::anyhow;
 pub use auth::Auth;
+pub use bytes::Bytes;
 pub use core::{ADriveCoreAPI, Result};
+pub use data::{
+    AsyncTaskResponse, AsyncTaskState, AudioStream, Category, ClearRecycleBinResponse,
+    CreateFileResponse, DriveId, ErrorResponse, FileEntry, FileId, FileType,
+    FlushUploadUrlResponse, GetAccessTokenResponse, GetAsyncTaskStateResponse,
+    GetDownloadUrlResponse, GetDriveInfoResponse, GetQRCodeImageResponse, GetQRCodeResponse,
+    GetQRCodeStatusResponse, GetSpaceInfoResponse, GetUserInfoResponse,
+    GetVideoPreviewPlayInfoResponse, IfNameExists, ImageMediaMetadata, InsufficientSpaceError,
+    ListFilesResponse, ListUploadedPartsResponse, LiveTranscodingSubtitleTask, LiveTranscodingTask,
+    PartInfo, PersonalSpaceInfo, PreviewTemplate, QRCodeStatus, TaskId, ThumbnailOptions,
+    UpdateVideoRecordResponse, UploadId, UploadedParts, VideoMediaMetadata, VideoPreviewMetadata,
+    VideoPreviewPlayInfo, VideoQuality, VideoStream,
+};
 use data::{
-    FileEntry, GetDriveInfoResponse as DriveInfo, GetSpaceInfoResponse as SpaceInfo,
-    GetUserInfoResponse as UserInfo, IfNameExists,
+    GetDriveInfoResponse as DriveInfo, GetSpaceInfoResponse as SpaceInfo,
+    GetUserInfoResponse as UserInfo,
 };
+use futures_util::{stream, StreamExt};
+pub use manager::{JobRecord, JobState, TransferJob, TransferManager, UploadSession};
+use progress::ProgressTracker;
+pub use progress::{Phase, Progress, ProgressEvent};
 pub use self_hosting::app as self_hosting_app;
 use std::{
+    collections::HashMap,
     fs,
-    path::PathBuf,
+    future::Future,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex, OnceLock},
+    time::Duration,
+};
+pub use throttle::BandwidthLimit;
+use tokio::io::AsyncRead;
+pub use transfer::{
+    DownloadDirSummary, DownloadOptions, UploadDirSummary, UploadKind, UploadOptions,
+    UploadOutcome, UploadStarted,
 };
 
 static TOKIO_RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
@@ -41,6 +71,30 @@ impl ADriveAPI {
         }
     }
 
+    pub fn with_auth(auth: Auth) -> Self {
+        Self {
+            inner: ADriveCoreAPI::with_auth(auth),
+        }
+    }
+
+    // caps all uploads of this client in bytes per second, None lifts the cap;
+    // running transfers pick up the new value with their next chunk
+    pub fn set_upload_limit(&self, bytes_per_sec: Option<u64>) {
+        self.inner.upload_limit().set(bytes_per_sec)
+    }
+
+    pub fn set_download_limit(&self, bytes_per_sec: Option<u64>) {
+        self.inner.download_limit().set(bytes_per_sec)
+    }
+
+    pub fn upload_limit(&self) -> Option<u64> {
+        self.inner.upload_limit().get()
+    }
+
+    pub fn download_limit(&self) -> Option<u64> {
+        self.inner.download_limit().get()
+    }
+
     pub async fn get_user_info(&self) -> Result<UserInfo> {
         self.inner.get_user_info().await
     }
@@ -49,15 +103,15 @@ impl ADriveAPI {
         self.inner.get_drive_info().await
     }
 
-    pub async fn get_default_drive_id(&self) -> Result<String> {
+    pub async fn get_default_drive_id(&self) -> Result<DriveId> {
         Ok(self.get_drive_info().await?.default_drive_id)
     }
 
-    pub async fn get_resource_drive_id(&self) -> Result<String> {
+    pub async fn get_resource_drive_id(&self) -> Result<DriveId> {
         Ok(self.get_drive_info().await?.resource_drive_id.unwrap())
     }
 
-    pub async fn get_backup_drive_id(&self) -> Result<String> {
+    pub async fn get_backup_drive_id(&self) -> Result<DriveId> {
         Ok(self.get_drive_info().await?.backup_drive_id.unwrap())
     }
 
@@ -75,16 +129,51 @@ impl ADriveAPI {
 
     pub async fn get_available_size(&self) -> Result<u64> {
         let space = self.get_space_info().await?.personal_space_info;
-        Ok(space.total_size - space.used_size)
+        Ok(space.total_size.saturating_sub(space.used_size))
+    }
+
+    async fn ensure_space(&self, needed: u64, options: &UploadOptions) -> Result<()> {
+        if !options.check_space {
+            return Ok(());
+        }
+        let available = self.get_available_size().await?;
+        Self::check_space(needed, available, options)
+    }
+
+    fn check_space(needed: u64, available: u64, options: &UploadOptions) -> Result<()> {
+        if needed.saturating_add(options.reserved_space) > available {
+            return Err(InsufficientSpaceError {
+                needed,
+                available,
+                reserved: options.reserved_space,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    pub async fn list_files(
+        &self,
+        drive_id: &DriveId,
+        parent_id: &FileId,
+    ) -> Result<Vec<FileEntry>> {
+        self.list_files_with_thumbnails(drive_id, parent_id, None)
+            .await
     }
 
-    pub async fn list_files(&self, drive_id: &str, parent_id: &str) -> Result<Vec<FileEntry>> {
+    // like list_files, with the thumbnail urls rendered for the options
+    pub async fn list_files_with_thumbnails(
+        &self,
+        drive_id: &DriveId,
+        parent_id: &FileId,
+        thumbnail: Option<&ThumbnailOptions>,
+    ) -> Result<Vec<FileEntry>> {
         let mut items = Vec::new();
         let mut marker = None;
         loop {
             let resp = self
                 .inner
-                .list_files(drive_id, parent_id, marker.as_deref())
+                .list_files(drive_id, parent_id, marker.as_deref(), thumbnail)
                 .await?;
             items.extend(resp.items);
             marker = resp.next_marker;
@@ -95,13 +184,23 @@ impl ADriveAPI {
         Ok(items)
     }
 
-    pub async fn search_files(&self, drive_id: &str, conditions: &str) -> Result<Vec<FileEntry>> {
+    pub async fn search_files(
+        &self,
+        drive_id: &DriveId,
+        conditions: &str,
+    ) -> Result<Vec<FileEntry>> {
         let mut items = Vec::new();
         let mut marker = None;
         loop {
             let resp = self
                 .inner
-                .search_files(drive_id, conditions, marker.as_deref(), Some("name ASC"))
+                .search_files(
+                    drive_id,
+                    conditions,
+                    marker.as_deref(),
+                    Some("name ASC"),
+                    None,
+                )
                 .await?;
             items.extend(resp.items);
             marker = resp.next_marker;
@@ -112,13 +211,13 @@ impl ADriveAPI {
         Ok(items)
     }
 
-    pub async fn list_starred_files(&self, drive_id: &str) -> Result<Vec<FileEntry>> {
+    pub async fn list_starred_files(&self, drive_id: &DriveId) -> Result<Vec<FileEntry>> {
         let mut items = Vec::new();
         let mut marker = None;
         loop {
             let resp = self
                 .inner
-                .list_starred_files(drive_id, marker.as_deref())
+                .list_starred_files(drive_id, marker.as_deref(), None)
                 .await?;
             items.extend(resp.items);
             marker = resp.next_marker;
@@ -129,49 +228,138 @@ impl ADriveAPI {
         Ok(items)
     }
 
-    pub async fn get_file_by_id(&self, drive_id: &str, file_id: &str) -> Result<FileEntry> {
-        self.inner.get_file_by_id(drive_id, file_id).await
+    pub async fn get_file_by_id(&self, drive_id: &DriveId, file_id: &FileId) -> Result<FileEntry> {
+        self.inner.get_file_by_id(drive_id, file_id, None).await
+    }
+
+    pub async fn get_file_with_thumbnail(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        thumbnail: &ThumbnailOptions,
+    ) -> Result<FileEntry> {
+        self.inner
+            .get_file_by_id(drive_id, file_id, Some(thumbnail))
+            .await
+    }
+
+    // the thumbnail of an entry from a listing as rendered for the options,
+    // images and videos only; with a cache_dir it is kept there and served
+    // without any request until the entry's content_hash changes
+    pub async fn download_thumbnail(
+        &self,
+        entry: &FileEntry,
+        thumbnail: &ThumbnailOptions,
+        cache_dir: Option<&Path>,
+    ) -> Result<Bytes> {
+        let cache_path = match (cache_dir, entry.content_hash.as_deref()) {
+            (Some(cache_dir), Some(content_hash)) => Some(cache_dir.join(
+                Self::thumbnail_cache_key(&entry.file_id, content_hash, thumbnail),
+            )),
+            _ => None,
+        };
+        if let Some(cached) = cache_path.as_deref().and_then(|p| fs::read(p).ok()) {
+            return Ok(cached.into());
+        }
+        // the entry's own url may be expired or rendered for other options
+        let detail = self
+            .get_file_with_thumbnail(&entry.drive_id, &entry.file_id, thumbnail)
+            .await?;
+        let url = detail
+            .thumbnail
+            .as_deref()
+            .filter(|url| !url.is_empty())
+            .ok_or_else(|| anyhow!("{} has no thumbnail", detail.name))?;
+        let bytes = self.inner.download_url(url).await?;
+        if let (Some(cache_dir), Some(cache_path)) = (cache_dir, cache_path) {
+            fs::create_dir_all(cache_dir)?;
+            fs::write(cache_path, &bytes)?;
+        }
+        Ok(bytes)
     }
 
-    pub async fn get_file_by_path(&self, drive_id: &str, file_path: &str) -> Result<FileEntry> {
+    pub async fn download_thumbnail_to(
+        &self,
+        entry: &FileEntry,
+        thumbnail: &ThumbnailOptions,
+        cache_dir: Option<&Path>,
+        target_path: &Path,
+    ) -> Result<()> {
+        let bytes = self.download_thumbnail(entry, thumbnail, cache_dir).await?;
+        Ok(fs::write(target_path, bytes)?)
+    }
+
+    fn thumbnail_cache_key(
+        file_id: &FileId,
+        content_hash: &str,
+        thumbnail: &ThumbnailOptions,
+    ) -> String {
+        format!(
+            "{}-{}-w{}-t{}.jpg",
+            file_id,
+            content_hash.to_lowercase(),
+            thumbnail.width.unwrap_or_default(),
+            thumbnail.video_time.unwrap_or_default().as_millis()
+        )
+    }
+
+    pub async fn get_file_by_path(&self, drive_id: &DriveId, file_path: &str) -> Result<FileEntry> {
         self.inner.get_file_by_path(drive_id, file_path).await
     }
 
     pub async fn batch_get_files(
         &self,
-        drive_id: &str,
-        file_ids: &[&str],
+        drive_id: &DriveId,
+        file_ids: &[FileId],
     ) -> Result<Vec<FileEntry>> {
         let mut items = Vec::new();
         for chunk in file_ids.chunks(constants::MAX_BATCH_SIZE) {
-            let resp = self.inner.batch_get_files(drive_id, chunk).await?;
+            let resp = self.inner.batch_get_files(drive_id, chunk, None).await?;
             items.extend(resp.items);
         }
         Ok(items)
     }
 
-    pub async fn get_download_url(&self, drive_id: &str, file_id: &str) -> Result<String> {
+    pub async fn get_download_url(&self, drive_id: &DriveId, file_id: &FileId) -> Result<String> {
         Ok(self.inner.get_download_url(drive_id, file_id).await?.url)
     }
 
     pub async fn download_file(
         &self,
-        drive_id: &str,
-        file_id: &str,
+        drive_id: &DriveId,
+        file_id: &FileId,
         target_dir: &str,
         rename_as: Option<&str>,
+        options: Option<&DownloadOptions>,
     ) -> Result<()> {
         let target_dir = utils::ensure_dirs(target_dir)?;
         let detail = self.get_file_by_id(drive_id, file_id).await?;
         let dst_path = target_dir.join(rename_as.unwrap_or(&detail.name));
-        let download_url = self.get_download_url(drive_id, file_id).await?;
+        let options = options.cloned().unwrap_or_default();
+        self.download_entry(&detail, &dst_path, &options).await
+    }
+
+    async fn download_entry(
+        &self,
+        detail: &FileEntry,
+        dst_path: &Path,
+        options: &DownloadOptions,
+    ) -> Result<()> {
+        let download_url = self
+            .get_download_url(&detail.drive_id, &detail.file_id)
+            .await?;
         let mut file = fs::OpenOptions::new()
-            .write(true)
             .append(true)
             .create(true)
-            .open(&dst_path)?;
+            .open(dst_path)?;
         let mut start = file.metadata().map_or(0, |m| m.len());
         let file_size = detail.size.unwrap();
+        let progress = ProgressTracker::new(
+            options.progress.clone(),
+            &detail.name,
+            Some(file_size.saturating_sub(start)),
+        );
+        let mut part = 0;
         loop {
             if start >= file_size {
                 break;
@@ -184,19 +372,113 @@ impl ADriveAPI {
             };
             self.inner
                 .download_file(
-                    drive_id,
-                    file_id,
+                    &detail.drive_id,
+                    &detail.file_id,
                     &mut file,
                     Some(&download_url),
                     Some(&start.to_string()),
                     end.as_deref(),
+                    options.bandwidth_limit.as_ref(),
                 )
                 .await?;
+            part += 1;
+            let len = constants::CHUNK_SIZE.min(file_size - start);
+            progress.advance(Phase::Downloading, len, part);
             start += constants::CHUNK_SIZE;
         }
+        progress.phase(Phase::Done);
         Ok(())
     }
 
+    async fn is_downloaded(detail: &FileEntry, local_path: &Path) -> Result<bool> {
+        let Ok(metadata) = fs::metadata(local_path) else {
+            return Ok(false);
+        };
+        if !metadata.is_file() || Some(metadata.len()) != detail.size {
+            return Ok(false);
+        }
+        let Some(content_hash) = detail.content_hash.as_deref() else {
+            return Ok(false);
+        };
+        // hashing reads the whole file, keep it off the runtime threads
+        let local_path = local_path.to_path_buf();
+        let local_hash = tokio::task::spawn_blocking(move || -> Result<String> {
+            utils::get_content_hash(&mut fs::File::open(local_path)?)
+        })
+        .await??;
+        Ok(local_hash.eq_ignore_ascii_case(content_hash))
+    }
+
+    // downloads the folder's content into local_dir, mirroring its sub folders
+    pub async fn download_dir(
+        &self,
+        drive_id: &DriveId,
+        folder_id: &FileId,
+        local_dir: &str,
+        options: Option<&DownloadOptions>,
+    ) -> Result<DownloadDirSummary> {
+        let options = options.cloned().unwrap_or_default();
+        let options = &options;
+        let local_dir = utils::ensure_dirs(local_dir)?;
+        let mut summary = DownloadDirSummary::default();
+        let mut files = Vec::new();
+        let mut folders = vec![(folder_id.clone(), local_dir)];
+        while let Some((folder_id, dir)) = folders.pop() {
+            let entries = self.list_files(drive_id, &folder_id).await?;
+            let mut name_counts = HashMap::new();
+            for entry in &entries {
+                *name_counts.entry(entry.name.clone()).or_insert(0) += 1;
+            }
+            for (name, count) in &name_counts {
+                if *count > 1 {
+                    // siblings sharing a name would all land on the same local path
+                    summary.failed.push((
+                        dir.join(name),
+                        anyhow!("{} remote entries are named {}", count, name),
+                    ));
+                }
+            }
+            for entry in entries {
+                if name_counts[&entry.name] > 1 {
+                    continue;
+                }
+                let local_path = dir.join(&entry.name);
+                if entry.is_dir() {
+                    fs::create_dir_all(&local_path)?;
+                    folders.push((entry.file_id, local_path));
+                } else {
+                    files.push((entry, local_path));
+                }
+            }
+        }
+
+        let mut results = stream::iter(files)
+            .map(|(entry, local_path)| async move {
+                let result = match Self::is_downloaded(&entry, &local_path).await {
+                    Ok(true) => Ok(false),
+                    Ok(false) => {
+                        // a partial or stale copy can't be resumed safely, start over
+                        let _ = fs::remove_file(&local_path);
+                        self.download_entry(&entry, &local_path, options)
+                            .await
+                            .map(|_| true)
+                    }
+                    Err(err) => Err(err),
+                };
+                (local_path, result)
+            })
+            .buffer_unordered(constants::MAX_CONCURRENCY);
+
+        while let Some((local_path, result)) = results.next().await {
+            match result {
+                Ok(true) => summary.downloaded.push(local_path),
+                Ok(false) => summary.skipped.push(local_path),
+                Err(err) => summary.failed.push((local_path, err)),
+            }
+        }
+        Ok(summary)
+    }
+
     fn runtime() -> &'static tokio::runtime::Runtime {
         TOKIO_RUNTIME.get_or_init(|| {
             tokio::runtime::Builder::new_multi_thread()
@@ -209,10 +491,11 @@ impl ADriveAPI {
 
     pub async fn concurrent_download_file(
         &self,
-        drive_id: &str,
-        file_id: &str,
+        drive_id: &DriveId,
+        file_id: &FileId,
         target_dir: &str,
         rename_as: Option<&str>,
+        options: Option<&DownloadOptions>,
     ) -> Result<()> {
         let target_dir = utils::ensure_dirs(target_dir)?;
         let detail = self.get_file_by_id(drive_id, file_id).await?;
@@ -221,6 +504,16 @@ impl ADriveAPI {
 
         let file_handle = Arc::new(Mutex::new(fs::File::create(&dst_path)?));
         let file_size = detail.size.unwrap();
+        let progress = Arc::new(ProgressTracker::new(
+            options.and_then(|o| o.progress.clone()),
+            &detail.name,
+            Some(file_size),
+        ));
+        let mut part = 0;
+        let limits = ADriveCoreAPI::limits(
+            self.inner.download_limit(),
+            options.and_then(|o| o.bandwidth_limit.as_ref()),
+        );
         let mut start = 0_u64;
         let mut futures = Vec::new();
         let token = self.inner.get_token().await?.refresh_token;
@@ -237,38 +530,118 @@ impl ADriveAPI {
             let download_url = download_url.clone();
             let token = token.clone();
             let file_handle = Arc::clone(&file_handle);
-            let future = Self::runtime().spawn(ADriveCoreAPI::download_file2(
-                file_handle,
-                download_url,
-                token,
-                Some(start.to_string()),
-                end,
-            ));
+            let progress = Arc::clone(&progress);
+            let limits = limits.clone();
+            part += 1;
+            let len = constants::CHUNK_SIZE.min(file_size - start);
+            let future = Self::runtime().spawn(async move {
+                ADriveCoreAPI::download_file2(
+                    file_handle,
+                    download_url,
+                    token,
+                    Some(start.to_string()),
+                    end,
+                    limits,
+                )
+                .await;
+                progress.advance(Phase::Downloading, len, part);
+            });
             futures.push(future);
             start += constants::CHUNK_SIZE;
         }
         for future in futures {
             future.await?;
         }
+        progress.phase(Phase::Done);
         Ok(())
     }
 
     pub async fn create_folder(
         &self,
-        drive_id: &str,
-        parent_id: &str,
+        drive_id: &DriveId,
+        parent_id: &FileId,
         name: &str,
-    ) -> Result<String> {
-        let resp = self.inner.create_folder(drive_id, parent_id, name).await?;
-        Ok(resp.file_id())
+        if_name_exists: Option<IfNameExists>,
+    ) -> Result<FileId> {
+        let if_name_exists = if_name_exists.unwrap_or_default();
+        let replaced = self
+            .check_name_conflict(drive_id, parent_id, name, if_name_exists)
+            .await?;
+        let resp = self
+            .inner
+            .create_folder(drive_id, parent_id, name, Some(if_name_exists))
+            .await?;
+        let file_id = resp.file_id()?;
+        self.recycle_replaced(drive_id, replaced, &file_id).await?;
+        Ok(file_id)
+    }
+
+    // errors if the name is taken under IfNameExists::Refuse, returns the entries
+    // to recycle under IfNameExists::Replace
+    async fn check_name_conflict(
+        &self,
+        drive_id: &DriveId,
+        parent_id: &FileId,
+        name: &str,
+        if_name_exists: IfNameExists,
+    ) -> Result<Vec<FileEntry>> {
+        let entries = self
+            .list_conflict_candidates(drive_id, parent_id, if_name_exists)
+            .await?;
+        Self::name_conflicts(&entries, name, if_name_exists)
+    }
+
+    // the entries of the folder a name can clash with, only listed for the
+    // modes which care about clashes
+    async fn list_conflict_candidates(
+        &self,
+        drive_id: &DriveId,
+        parent_id: &FileId,
+        if_name_exists: IfNameExists,
+    ) -> Result<Vec<FileEntry>> {
+        if !matches!(if_name_exists, IfNameExists::Refuse | IfNameExists::Replace) {
+            return Ok(Vec::new());
+        }
+        self.list_files(drive_id, parent_id).await
+    }
+
+    fn name_conflicts(
+        entries: &[FileEntry],
+        name: &str,
+        if_name_exists: IfNameExists,
+    ) -> Result<Vec<FileEntry>> {
+        let existing: Vec<FileEntry> = entries
+            .iter()
+            .filter(|entry| entry.name == name)
+            .cloned()
+            .collect();
+        if if_name_exists == IfNameExists::Refuse && !existing.is_empty() {
+            return Err(anyhow!("{} already exists", name));
+        }
+        Ok(existing)
+    }
+
+    async fn recycle_replaced(
+        &self,
+        drive_id: &DriveId,
+        replaced: Vec<FileEntry>,
+        new_file_id: &FileId,
+    ) -> Result<()> {
+        for entry in replaced {
+            if &entry.file_id != new_file_id {
+                self.recycle_file(drive_id, &entry.file_id).await?;
+            }
+        }
+        Ok(())
     }
 
     pub async fn upload_file(
         &self,
-        drive_id: &str,
-        parent_id: &str,
+        drive_id: &DriveId,
+        parent_id: &FileId,
         file_path: &str,
-    ) -> Result<()> {
+        options: Option<&UploadOptions>,
+    ) -> Result<UploadOutcome> {
         let file_path = PathBuf::from(file_path);
         if file_path.is_dir() {
             return Err(anyhow!("file_path is a directory"));
@@ -276,18 +649,394 @@ impl ADriveAPI {
 
         let file_name = file_path.file_name().unwrap().to_str().unwrap();
         let mut file = fs::File::open(&file_path)?;
-        self.inner
-            .upload_file(drive_id, parent_id, file_name, &mut file)
-            .await
+        let options = options.cloned().unwrap_or_default();
+        self.ensure_space(file.metadata()?.len(), &options).await?;
+        let replaced = self
+            .check_name_conflict(drive_id, parent_id, file_name, options.if_name_exists)
+            .await?;
+        let outcome = self
+            .inner
+            .upload_file(drive_id, parent_id, file_name, &mut file, &options)
+            .await?;
+        self.recycle_replaced(drive_id, replaced, &outcome.file.file_id)
+            .await?;
+        Ok(outcome)
+    }
+
+    // file_id and upload_id come from UploadOptions::upload_started; with
+    // IfNameExists::Replace the other files of that name are recycled once
+    // the upload completes
+    pub async fn resume_upload(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        upload_id: &UploadId,
+        file_path: &str,
+        options: Option<&UploadOptions>,
+    ) -> Result<UploadOutcome> {
+        let mut file = fs::File::open(file_path)?;
+        let file_size = file.metadata()?.len();
+        let options = options.cloned().unwrap_or_default();
+        let outcome = self
+            .inner
+            .resume_upload(drive_id, file_id, upload_id, file_size, &mut file, &options)
+            .await?;
+        if options.if_name_exists == IfNameExists::Replace {
+            let entries = self
+                .list_files(drive_id, &outcome.file.parent_file_id)
+                .await?;
+            let replaced =
+                Self::name_conflicts(&entries, &outcome.file.name, IfNameExists::Replace)?;
+            self.recycle_replaced(drive_id, replaced, &outcome.file.file_id)
+                .await?;
+        }
+        Ok(outcome)
+    }
+
+    // copies a file of this account into another one; only the hashes and the
+    // proof code bytes are read when the target's server already knows the
+    // content, otherwise the parts are streamed across without touching disk
+    pub async fn rapid_transfer(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        target: &ADriveAPI,
+        target_drive_id: &DriveId,
+        target_parent_id: &FileId,
+        options: Option<&UploadOptions>,
+    ) -> Result<UploadOutcome> {
+        let options = options.cloned().unwrap_or_default();
+        let detail = self.get_file_by_id(drive_id, file_id).await?;
+        let file_size = detail
+            .size
+            .ok_or_else(|| anyhow!("{} is not a file", detail.name))?;
+        target.ensure_space(file_size, &options).await?;
+        let progress =
+            ProgressTracker::new(options.progress.clone(), &detail.name, Some(file_size));
+        let part_size = ADriveCoreAPI::part_size_for(file_size, options.part_size)?;
+        let part_info_list = ADriveCoreAPI::create_part_info_list(file_size, part_size)?;
+        let replaced = target
+            .check_name_conflict(
+                target_drive_id,
+                target_parent_id,
+                &detail.name,
+                options.if_name_exists,
+            )
+            .await?;
+
+        progress.phase(Phase::RapidCheck);
+        let resp = if let Some(content_hash) = detail.content_hash.as_deref() {
+            let token = target.inner.get_token().await?;
+            let (start, end) = utils::get_proof_range(file_size, &token.access_token)?;
+            let proof_code = if start < end {
+                let download_url = self.get_download_url(drive_id, file_id).await?;
+                let buf = self
+                    .inner
+                    .download_range(&download_url, start, end - 1, None)
+                    .await?;
+                utils::encode_proof_code(&buf)
+            } else {
+                String::new()
+            };
+            target
+                .inner
+                .check_content_hash(
+                    target_drive_id,
+                    target_parent_id,
+                    &detail.name,
+                    part_info_list,
+                    content_hash,
+                    &proof_code,
+                    file_size,
+                    &options,
+                )
+                .await?
+        } else {
+            target
+                .inner
+                .create_multipart_upload(
+                    target_drive_id,
+                    target_parent_id,
+                    &detail.name,
+                    Some(part_info_list),
+                    &options,
+                )
+                .await?
+        };
+        if resp.exist() {
+            return Err(anyhow!("{} already exists", detail.name));
+        }
+
+        let outcome = if resp.content_hash_matched() {
+            let file = target
+                .get_file_by_id(target_drive_id, &resp.file_id()?)
+                .await?;
+            progress.phase(Phase::Done);
+            UploadOutcome {
+                file,
+                kind: UploadKind::Rapid,
+                bytes_sent: 0,
+            }
+        } else {
+            let new_file_id = resp.file_id()?;
+            let upload_id = resp.upload_id()?;
+            let part_info_list = resp.part_info_list()?;
+            let mut bytes_sent = 0;
+            for part_info in part_info_list.iter() {
+                let start = (part_info.part_number as u64 - 1) * part_size;
+                let end = (start + part_size).min(file_size) - 1;
+                // the download url expires after 900s, a fresh one per part outlives slow uploads
+                let download_url = self.get_download_url(drive_id, file_id).await?;
+                let buffer = self
+                    .inner
+                    .download_range(&download_url, start, end, None)
+                    .await?;
+                let len = buffer.len() as u64;
+                target
+                    .inner
+                    .upload_part(part_info, buffer.to_vec(), options.bandwidth_limit.as_ref())
+                    .await?;
+                bytes_sent += len;
+                progress.advance(Phase::Uploading, len, part_info.part_number as u32);
+            }
+            progress.phase(Phase::Completing);
+            let uploaded = target
+                .inner
+                .list_uploaded_parts(target_drive_id, &new_file_id, &upload_id)
+                .await?;
+            if uploaded.len() != part_info_list.len() {
+                return Err(anyhow!("part upload failed"));
+            }
+            let file = target
+                .inner
+                .complete_multipart_upload(target_drive_id, &new_file_id, &upload_id)
+                .await?;
+            progress.phase(Phase::Done);
+            UploadOutcome {
+                file,
+                kind: UploadKind::Transferred,
+                bytes_sent,
+            }
+        };
+        target
+            .recycle_replaced(target_drive_id, replaced, &outcome.file.file_id)
+            .await?;
+        Ok(outcome)
+    }
+
+    fn is_ignored(ignore: &[&str], name: &str, relative_path: &Path) -> bool {
+        let relative_path = relative_path.to_string_lossy().replace('\\', "/");
+        ignore.iter().any(|pattern| {
+            utils::wildcard_match(pattern, name) || utils::wildcard_match(pattern, &relative_path)
+        })
+    }
+
+    // uploads local_dir as a folder of the same name under parent_id, existing
+    // remote folders are reused so the upload can be run again
+    pub async fn upload_dir(
+        &self,
+        drive_id: &DriveId,
+        parent_id: &FileId,
+        local_dir: &str,
+        ignore: &[&str],
+        options: Option<&UploadOptions>,
+    ) -> Result<UploadDirSummary> {
+        let options = &options.cloned().unwrap_or_default();
+        let local_dir = fs::canonicalize(local_dir)?;
+        if !local_dir.is_dir() {
+            return Err(anyhow!("local_dir is not a directory"));
+        }
+        let dir_name = local_dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow!("local_dir has no valid name"))?;
+
+        let mut summary = UploadDirSummary::default();
+        let mut dirs = Vec::new();
+        let mut local_files = Vec::new();
+        let mut pending = vec![local_dir.clone()];
+        while let Some(dir) = pending.pop() {
+            for entry in fs::read_dir(&dir)? {
+                let entry = entry?;
+                let path = entry.path();
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                let relative_path = path.strip_prefix(&local_dir)?;
+                // file_type doesn't follow symlinks, so they end up skipped
+                let file_type = entry.file_type()?;
+                if Self::is_ignored(ignore, &name, relative_path) {
+                    summary.skipped.push(path);
+                } else if file_type.is_dir() {
+                    dirs.push(path.clone());
+                    pending.push(path);
+                } else if file_type.is_file() {
+                    local_files.push(path);
+                } else {
+                    summary.skipped.push(path);
+                }
+            }
+        }
+        // checked before anything is created remotely, files which exist
+        // already or end up skipped still count so this may overestimate
+        let mut total_size = 0;
+        for path in local_files.iter() {
+            total_size += fs::metadata(path)?.len();
+        }
+        self.ensure_space(total_size, options).await?;
+
+        // parents always come before their children in dirs
+        let resp = self
+            .inner
+            .create_folder(drive_id, parent_id, dir_name, Some(IfNameExists::Refuse))
+            .await?;
+        summary.folder_id = resp.file_id()?;
+        let mut folder_ids = HashMap::from([(local_dir.clone(), summary.folder_id.clone())]);
+        for dir in dirs {
+            let name = dir.file_name().unwrap().to_string_lossy();
+            // the parent failed already, the failure is reported once for it
+            let Some(parent_folder_id) = folder_ids.get(dir.parent().unwrap()) else {
+                continue;
+            };
+            let result = self
+                .inner
+                .create_folder(
+                    drive_id,
+                    parent_folder_id,
+                    &name,
+                    Some(IfNameExists::Refuse),
+                )
+                .await
+                .and_then(|resp| resp.file_id());
+            match result {
+                Ok(folder_id) => {
+                    folder_ids.insert(dir, folder_id);
+                }
+                Err(err) => summary.failed.push((dir, err)),
+            }
+        }
+        // files below a folder which couldn't be created are skipped, the
+        // folder itself is in failed
+        local_files.retain(|path| {
+            let created = folder_ids.contains_key(path.parent().unwrap());
+            if !created {
+                summary.skipped.push(path.clone());
+            }
+            created
+        });
+
+        let check_existing = matches!(
+            options.if_name_exists,
+            IfNameExists::Refuse | IfNameExists::Replace
+        );
+        let mut remote_files = HashMap::new();
+        let mut files = Vec::new();
+        for path in local_files {
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            let folder_id = folder_ids[path.parent().unwrap()].clone();
+            let mut existing = Vec::new();
+            if check_existing {
+                if !remote_files.contains_key(&folder_id) {
+                    let entries = self.list_files(drive_id, &folder_id).await?;
+                    remote_files.insert(folder_id.clone(), entries);
+                }
+                existing = remote_files[&folder_id]
+                    .iter()
+                    .filter(|entry| entry.name == name)
+                    .map(|entry| entry.file_id.clone())
+                    .collect();
+            }
+            if options.if_name_exists == IfNameExists::Refuse && !existing.is_empty() {
+                summary.skipped.push(path);
+            } else {
+                files.push((folder_id, name, path, existing));
+            }
+        }
+        let mut results = stream::iter(files)
+            .map(|(folder_id, name, path, existing)| async move {
+                let result = async {
+                    let mut file = fs::File::open(&path)?;
+                    let outcome = self
+                        .inner
+                        .upload_file(drive_id, &folder_id, &name, &mut file, options)
+                        .await?;
+                    for file_id in existing.iter().filter(|id| **id != outcome.file.file_id) {
+                        self.recycle_file(drive_id, file_id).await?;
+                    }
+                    Ok(outcome)
+                }
+                .await;
+                (path, result)
+            })
+            .buffer_unordered(constants::MAX_CONCURRENCY);
+
+        while let Some((path, result)) = results.next().await {
+            match result {
+                Ok(outcome) if outcome.kind == UploadKind::Rapid => {
+                    summary.rapid_uploaded.push(path)
+                }
+                Ok(_) => summary.uploaded.push(path),
+                Err(err) => summary.failed.push((path, err)),
+            }
+        }
+        Ok(summary)
+    }
+
+    pub async fn upload_bytes(
+        &self,
+        drive_id: &DriveId,
+        parent_id: &FileId,
+        file_name: &str,
+        data: Bytes,
+        options: Option<&UploadOptions>,
+    ) -> Result<FileEntry> {
+        let options = options.cloned().unwrap_or_default();
+        self.ensure_space(data.len() as u64, &options).await?;
+        let replaced = self
+            .check_name_conflict(drive_id, parent_id, file_name, options.if_name_exists)
+            .await?;
+        let file = self
+            .inner
+            .upload_bytes(drive_id, parent_id, file_name, data, &options)
+            .await?;
+        self.recycle_replaced(drive_id, replaced, &file.file_id)
+            .await?;
+        Ok(file)
+    }
+
+    // with size None the free space can't be checked up front, and the parts
+    // grow as the stream does, which tops out at ~3.8TB with the default part
+    // size, past that the upload fails
+    pub async fn upload_stream<R: AsyncRead + Unpin + Send>(
+        &self,
+        drive_id: &DriveId,
+        parent_id: &FileId,
+        file_name: &str,
+        mut reader: R,
+        size: Option<u64>,
+        options: Option<&UploadOptions>,
+    ) -> Result<FileEntry> {
+        let options = options.cloned().unwrap_or_default();
+        if let Some(size) = size {
+            self.ensure_space(size, &options).await?;
+        }
+        let replaced = self
+            .check_name_conflict(drive_id, parent_id, file_name, options.if_name_exists)
+            .await?;
+        let file = self
+            .inner
+            .upload_stream(drive_id, parent_id, file_name, &mut reader, size, &options)
+            .await?;
+        self.recycle_replaced(drive_id, replaced, &file.file_id)
+            .await?;
+        Ok(file)
     }
 
-    pub async fn star_file(&self, drive_id: &str, file_id: &str) -> Result<FileEntry> {
+    pub async fn star_file(&self, drive_id: &DriveId, file_id: &FileId) -> Result<FileEntry> {
         self.inner
             .update_file(drive_id, file_id, None, None, Some(true))
             .await
     }
 
-    pub async fn unstar_file(&self, drive_id: &str, file_id: &str) -> Result<FileEntry> {
+    pub async fn unstar_file(&self, drive_id: &DriveId, file_id: &FileId) -> Result<FileEntry> {
         self.inner
             .update_file(drive_id, file_id, None, None, Some(false))
             .await
@@ -295,54 +1044,445 @@ impl ADriveAPI {
 
     pub async fn rename_file(
         &self,
-        drive_id: &str,
-        file_id: &str,
+        drive_id: &DriveId,
+        file_id: &FileId,
         rename_as: &str,
+        if_name_exists: Option<IfNameExists>,
     ) -> Result<FileEntry> {
-        self.inner
+        let if_name_exists = if_name_exists.unwrap_or_default();
+        let detail = self.get_file_by_id(drive_id, file_id).await?;
+        let replaced = self
+            .check_name_conflict(drive_id, &detail.parent_file_id, rename_as, if_name_exists)
+            .await?;
+        let file = self
+            .inner
             .update_file(
                 drive_id,
                 file_id,
                 Some(rename_as),
-                Some(IfNameExists::AutoRename),
+                Some(if_name_exists),
                 None,
             )
-            .await
+            .await?;
+        self.recycle_replaced(drive_id, replaced, file_id).await?;
+        Ok(file)
     }
 
     pub async fn move_file(
         &self,
-        drive_id: &str,
-        file_id: &str,
-        target_parent_id: &str,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        target_parent_id: &FileId,
         rename_as: Option<&str>,
+        if_name_exists: Option<IfNameExists>,
     ) -> Result<()> {
-        self.inner
-            .move_file(drive_id, file_id, target_parent_id, rename_as)
+        let if_name_exists = if_name_exists.unwrap_or_default();
+        let entries = self
+            .list_conflict_candidates(drive_id, target_parent_id, if_name_exists)
             .await?;
-        Ok(())
+        self.move_file_among(
+            drive_id,
+            file_id,
+            target_parent_id,
+            rename_as,
+            if_name_exists,
+            &entries,
+        )
+        .await
+    }
+
+    // move_file with the entries of the target folder listed by the caller
+    async fn move_file_among(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        target_parent_id: &FileId,
+        rename_as: Option<&str>,
+        if_name_exists: IfNameExists,
+        entries: &[FileEntry],
+    ) -> Result<()> {
+        let replaced = match rename_as {
+            Some(name) => Self::name_conflicts(entries, name, if_name_exists)?,
+            None if entries.is_empty() => Vec::new(),
+            None => {
+                let detail = self.get_file_by_id(drive_id, file_id).await?;
+                Self::name_conflicts(entries, &detail.name, if_name_exists)?
+            }
+        };
+        let resp = self
+            .inner
+            .move_file(
+                drive_id,
+                file_id,
+                target_parent_id,
+                rename_as,
+                Some(if_name_exists),
+            )
+            .await?;
+        self.wait_async_task_of(&resp).await?;
+        self.recycle_replaced(drive_id, replaced, file_id).await
     }
 
     pub async fn copy_file(
         &self,
-        drive_id: &str,
-        file_id: &str,
-        target_parent_id: &str,
-    ) -> Result<String> {
+        drive_id: &DriveId,
+        file_id: &FileId,
+        target_parent_id: &FileId,
+        if_name_exists: Option<IfNameExists>,
+    ) -> Result<FileId> {
+        let if_name_exists = if_name_exists.unwrap_or_default();
+        let entries = self
+            .list_conflict_candidates(drive_id, target_parent_id, if_name_exists)
+            .await?;
+        self.copy_file_among(
+            drive_id,
+            file_id,
+            target_parent_id,
+            if_name_exists,
+            &entries,
+        )
+        .await
+    }
+
+    // copy_file with the entries of the target folder listed by the caller
+    async fn copy_file_among(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        target_parent_id: &FileId,
+        if_name_exists: IfNameExists,
+        entries: &[FileEntry],
+    ) -> Result<FileId> {
+        let replaced = if entries.is_empty() {
+            Vec::new()
+        } else {
+            let detail = self.get_file_by_id(drive_id, file_id).await?;
+            Self::name_conflicts(entries, &detail.name, if_name_exists)?
+        };
         let resp = self
             .inner
-            .copy_file(drive_id, file_id, target_parent_id)
+            .copy_file(
+                drive_id,
+                file_id,
+                target_parent_id,
+                if_name_exists == IfNameExists::AutoRename,
+            )
+            .await?;
+        self.wait_async_task_of(&resp).await?;
+        self.recycle_replaced(drive_id, replaced, &resp.file_id)
             .await?;
         Ok(resp.file_id)
     }
 
-    pub async fn recycle_file(&self, drive_id: &str, file_id: &str) -> Result<()> {
-        self.inner.recycle_file(drive_id, file_id).await?;
-        Ok(())
+    pub async fn recycle_file(&self, drive_id: &DriveId, file_id: &FileId) -> Result<()> {
+        let resp = self.inner.recycle_file(drive_id, file_id).await?;
+        self.wait_async_task_of(&resp).await
     }
 
-    pub async fn delete_file(&self, drive_id: &str, file_id: &str) -> Result<()> {
-        self.inner.delete_file(drive_id, file_id).await?;
+    pub async fn delete_file(&self, drive_id: &DriveId, file_id: &FileId) -> Result<()> {
+        let resp = self.inner.delete_file(drive_id, file_id).await?;
+        self.wait_async_task_of(&resp).await
+    }
+
+    // the transcoded hls streams of a video and its subtitles; the urls stay
+    // valid for url_expire_sec, 900 seconds by default and 14400 at most
+    pub async fn get_video_preview_play_info(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        url_expire_sec: Option<u64>,
+    ) -> Result<VideoPreviewPlayInfo> {
+        Ok(self
+            .inner
+            .get_video_preview_play_info(drive_id, file_id, url_expire_sec)
+            .await?
+            .video_preview_play_info)
+    }
+
+    // saves where playback of the video stopped, which also puts it on top of
+    // the recently played list; other devices resume from the file's play_cursor
+    pub async fn update_play_cursor(
+        &self,
+        drive_id: &DriveId,
+        file_id: &FileId,
+        play_cursor: Duration,
+        duration: Option<Duration>,
+    ) -> Result<()> {
+        self.inner
+            .update_video_record(drive_id, file_id, play_cursor, duration)
+            .await?;
         Ok(())
     }
+
+    pub async fn list_recent_videos(&self) -> Result<Vec<FileEntry>> {
+        Ok(self.inner.list_recent_videos().await?.items)
+    }
+
+    // lists everything in the recycle bin, following the pages
+    pub async fn list_recyclebin(&self, drive_id: &DriveId) -> Result<Vec<FileEntry>> {
+        let mut items = Vec::new();
+        let mut marker = None;
+        loop {
+            let resp = self
+                .inner
+                .list_recyclebin(drive_id, marker.as_deref(), None)
+                .await?;
+            items.extend(resp.items);
+            marker = resp.next_marker;
+            if marker.is_none() || marker.as_deref() == Some("") {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    // moves the file back to where it was recycled from
+    pub async fn restore_file(&self, drive_id: &DriveId, file_id: &FileId) -> Result<()> {
+        let resp = self.inner.restore_file(drive_id, file_id).await?;
+        self.wait_async_task_of(&resp).await
+    }
+
+    // deletes everything in the recycle bin for good
+    pub async fn clear_recyclebin(&self, drive_id: &DriveId) -> Result<()> {
+        let resp = self.inner.clear_recyclebin(drive_id).await?;
+        match &resp.async_task_id {
+            Some(async_task_id) if !async_task_id.as_str().is_empty() => {
+                self.wait_async_task(async_task_id).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // polls the task until it is no longer running, errors if it failed,
+    // reports a state this client does not know or still runs after
+    // ASYNC_TASK_TIMEOUT_SECS; the pause between polls doubles up to
+    // ASYNC_TASK_MAX_POLL_MILLIS
+    pub async fn wait_async_task(&self, async_task_id: &TaskId) -> Result<()> {
+        let timeout = Duration::from_secs(constants::ASYNC_TASK_TIMEOUT_SECS);
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut delay = Duration::from_millis(constants::ASYNC_TASK_POLL_MILLIS);
+        loop {
+            let resp = self.inner.get_async_task_state(async_task_id).await?;
+            match resp.state {
+                AsyncTaskState::Succeed => return Ok(()),
+                AsyncTaskState::Failed => {
+                    return Err(anyhow!("async task {} failed", async_task_id))
+                }
+                AsyncTaskState::Running => {
+                    if tokio::time::Instant::now() + delay > deadline {
+                        return Err(anyhow!(
+                            "async task {} is still running after {}s",
+                            async_task_id,
+                            timeout.as_secs()
+                        ));
+                    }
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2)
+                        .min(Duration::from_millis(constants::ASYNC_TASK_MAX_POLL_MILLIS));
+                }
+                AsyncTaskState::Unknown(state) => {
+                    return Err(anyhow!(
+                        "async task {} is in unknown state {}",
+                        async_task_id,
+                        state
+                    ))
+                }
+            }
+        }
+    }
+
+    // folder operations finish in the background, files are done right away
+    async fn wait_async_task_of(&self, resp: &AsyncTaskResponse) -> Result<()> {
+        match &resp.async_task_id {
+            Some(async_task_id) if !async_task_id.as_str().is_empty() => {
+                self.wait_async_task(async_task_id).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // runs op for every file id, MAX_BATCH_SIZE at a time with at most
+    // MAX_CONCURRENCY requests in flight; results keep the order of file_ids
+    async fn batch<'a, T, F, Fut>(file_ids: &'a [FileId], op: F) -> Vec<(FileId, Result<T>)>
+    where
+        F: Fn(&'a FileId) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut results = Vec::with_capacity(file_ids.len());
+        for chunk in file_ids.chunks(constants::MAX_BATCH_SIZE) {
+            let chunk_results: Vec<_> = stream::iter(chunk)
+                .map(|file_id| {
+                    let result = op(file_id);
+                    async move { (file_id.clone(), result.await) }
+                })
+                .buffered(constants::MAX_CONCURRENCY)
+                .collect()
+                .await;
+            results.extend(chunk_results);
+        }
+        results
+    }
+
+    fn fail_all<T>(file_ids: &[FileId], err: &anyhow::Error) -> Vec<(FileId, Result<T>)> {
+        file_ids
+            .iter()
+            .map(|file_id| (file_id.clone(), Err(anyhow!("{:#}", err))))
+            .collect()
+    }
+
+    pub async fn batch_move_files(
+        &self,
+        drive_id: &DriveId,
+        file_ids: &[FileId],
+        target_parent_id: &FileId,
+        if_name_exists: Option<IfNameExists>,
+    ) -> Vec<(FileId, Result<()>)> {
+        // the target folder is listed once for the whole batch
+        let if_name_exists = if_name_exists.unwrap_or_default();
+        let entries = match self
+            .list_conflict_candidates(drive_id, target_parent_id, if_name_exists)
+            .await
+        {
+            Ok(entries) => entries,
+            Err(err) => return Self::fail_all(file_ids, &err),
+        };
+        Self::batch(file_ids, |file_id| {
+            self.move_file_among(
+                drive_id,
+                file_id,
+                target_parent_id,
+                None,
+                if_name_exists,
+                &entries,
+            )
+        })
+        .await
+    }
+
+    // the result of a copied file is the id of its copy
+    pub async fn batch_copy_files(
+        &self,
+        drive_id: &DriveId,
+        file_ids: &[FileId],
+        target_parent_id: &FileId,
+        if_name_exists: Option<IfNameExists>,
+    ) -> Vec<(FileId, Result<FileId>)> {
+        let if_name_exists = if_name_exists.unwrap_or_default();
+        let entries = match self
+            .list_conflict_candidates(drive_id, target_parent_id, if_name_exists)
+            .await
+        {
+            Ok(entries) => entries,
+            Err(err) => return Self::fail_all(file_ids, &err),
+        };
+        Self::batch(file_ids, |file_id| {
+            self.copy_file_among(
+                drive_id,
+                file_id,
+                target_parent_id,
+                if_name_exists,
+                &entries,
+            )
+        })
+        .await
+    }
+
+    pub async fn batch_recycle_files(
+        &self,
+        drive_id: &DriveId,
+        file_ids: &[FileId],
+    ) -> Vec<(FileId, Result<()>)> {
+        Self::batch(file_ids, |file_id| self.recycle_file(drive_id, file_id)).await
+    }
+
+    pub async fn batch_delete_files(
+        &self,
+        drive_id: &DriveId,
+        file_ids: &[FileId],
+    ) -> Vec<(FileId, Result<()>)> {
+        Self::batch(file_ids, |file_id| self.delete_file(drive_id, file_id)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test(start_paused = true)]
+    async fn batch_keeps_order_and_bounds_concurrency() {
+        let ids: Vec<FileId> = (0..250).map(|i| FileId::new(i.to_string())).collect();
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let results = ADriveAPI::batch(&ids, |file_id| {
+            let (in_flight, peak) = (&in_flight, &peak);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                let n: u64 = file_id.as_str().parse()?;
+                tokio::time::sleep(Duration::from_millis(250 - n)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                anyhow::ensure!(n % 100 != 7, "{} failed", n);
+                Ok(n)
+            }
+        })
+        .await;
+
+        assert_eq!(results.len(), ids.len());
+        assert!(results.iter().map(|(id, _)| id).eq(ids.iter()));
+        let failed: Vec<_> = results.iter().filter(|(_, r)| r.is_err()).collect();
+        assert_eq!(failed.len(), 3);
+        assert_eq!(peak.load(Ordering::SeqCst), constants::MAX_CONCURRENCY);
+    }
+
+    #[test]
+    fn check_space_keeps_reserved_space_free() {
+        let options = UploadOptions {
+            reserved_space: 10,
+            ..Default::default()
+        };
+        assert!(ADriveAPI::check_space(90, 100, &options).is_ok());
+        let err = ADriveAPI::check_space(91, 100, &options).unwrap_err();
+        let err = err.downcast::<InsufficientSpaceError>().unwrap();
+        assert_eq!((err.needed, err.available, err.reserved), (91, 100, 10));
+        assert!(ADriveAPI::check_space(u64::MAX, 100, &options).is_err());
+        assert!(ADriveAPI::check_space(100, 100, &UploadOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn thumbnail_cache_key_tracks_content_and_options() {
+        let options = ThumbnailOptions {
+            width: Some(480),
+            video_time: Some(Duration::from_millis(1500)),
+        };
+        let file_id = FileId::from("f1");
+        let key = ADriveAPI::thumbnail_cache_key(&file_id, "ABC", &options);
+        assert_eq!(key, "f1-abc-w480-t1500.jpg");
+        assert_ne!(
+            key,
+            ADriveAPI::thumbnail_cache_key(&file_id, "abd", &options)
+        );
+        assert_ne!(
+            key,
+            ADriveAPI::thumbnail_cache_key(&file_id, "abc", &ThumbnailOptions::default())
+        );
+    }
+
+    #[test]
+    fn ignore_patterns_match_names_and_relative_paths() {
+        let ignore = ["*.tmp", "target/*", "docs/?.md"];
+        let is_ignored = |path: &str| {
+            let path = Path::new(path);
+            let name = path.file_name().unwrap().to_str().unwrap();
+            ADriveAPI::is_ignored(&ignore, name, path)
+        };
+        assert!(is_ignored("a.tmp"));
+        assert!(is_ignored("src/deep/b.tmp"));
+        assert!(is_ignored("target/debug"));
+        assert!(!is_ignored("src/target"));
+        assert!(is_ignored("docs/a.md"));
+        assert!(!is_ignored("docs/ab.md"));
+        assert!(!is_ignored("src/docs/a.md"));
+        assert!(!is_ignored("src/main.rs"));
+    }
 }
@@ -0,0 +1,632 @@
+use crate::{
+    ADriveAPI, BandwidthLimit, DownloadOptions, DriveId, FileId, IfNameExists, Progress, Result,
+    UploadId, UploadOptions,
+};
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TransferJob {
+    Upload {
+        drive_id: DriveId,
+        parent_id: FileId,
+        file_path: String,
+        #[serde(default, with = "name_policy")]
+        if_name_exists: IfNameExists,
+        // bytes per second, on top of the client wide limit
+        #[serde(default)]
+        bandwidth_limit: Option<u64>,
+    },
+    Download {
+        drive_id: DriveId,
+        file_id: FileId,
+        target_dir: String,
+        // bytes per second, on top of the client wide limit
+        #[serde(default)]
+        bandwidth_limit: Option<u64>,
+    },
+}
+
+// IfNameExists is written for the server, which gets Replace as ignore
+mod name_policy {
+    use crate::IfNameExists;
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    const NAMES: &[&str] = &["auto_rename", "refuse", "ignore", "replace"];
+
+    pub fn serialize<S: Serializer>(
+        if_name_exists: &IfNameExists,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let name = match if_name_exists {
+            IfNameExists::AutoRename => NAMES[0],
+            IfNameExists::Refuse => NAMES[1],
+            IfNameExists::Ignore => NAMES[2],
+            IfNameExists::Replace => NAMES[3],
+        };
+        name.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<IfNameExists, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "auto_rename" => Ok(IfNameExists::AutoRename),
+            "refuse" => Ok(IfNameExists::Refuse),
+            "ignore" => Ok(IfNameExists::Ignore),
+            "replace" => Ok(IfNameExists::Replace),
+            name => Err(de::Error::unknown_variant(name, NAMES)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Paused,
+    Failed,
+    Done,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub id: u64,
+    pub job: TransferJob,
+    pub state: JobState,
+    pub attempts: u32,
+    pub error: Option<String>,
+    // the multipart upload an earlier attempt created, the next one continues it
+    #[serde(default)]
+    pub upload: Option<UploadSession>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadSession {
+    pub file_id: FileId,
+    pub upload_id: UploadId,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct QueueFile {
+    next_id: u64,
+    jobs: BTreeMap<u64, JobRecord>,
+}
+
+// runs queued uploads and downloads in the background and keeps the queue on
+// disk so unfinished jobs are picked up again after a restart; a paused or
+// retried upload continues the multipart upload it started and only starts
+// over when the server no longer accepts it, a download continues from the
+// local file's end
+pub struct TransferManager {
+    api: Arc<ADriveAPI>,
+    state_path: PathBuf,
+    max_concurrency: usize,
+    max_attempts: u32,
+    progress: Mutex<Option<Arc<dyn Progress>>>,
+    queue: Mutex<QueueFile>,
+    running: Mutex<HashMap<u64, CancellationToken>>,
+    wakeup: Notify,
+    shutdown: CancellationToken,
+}
+
+impl TransferManager {
+    pub fn open(
+        api: Arc<ADriveAPI>,
+        state_path: impl Into<PathBuf>,
+        max_concurrency: usize,
+        max_attempts: u32,
+    ) -> Result<Arc<Self>> {
+        let state_path = state_path.into();
+        let mut queue: QueueFile = match fs::read(&state_path) {
+            Ok(content) => serde_json::from_slice(&content)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => QueueFile::default(),
+            Err(err) => return Err(err.into()),
+        };
+        // whatever was running when the process stopped has to run again
+        for record in queue.jobs.values_mut() {
+            if record.state == JobState::Running {
+                record.state = JobState::Queued;
+            }
+        }
+        Ok(Arc::new(Self {
+            api,
+            state_path,
+            max_concurrency: max_concurrency.max(1),
+            max_attempts: max_attempts.max(1),
+            progress: Mutex::new(None),
+            queue: Mutex::new(queue),
+            running: Mutex::new(HashMap::new()),
+            wakeup: Notify::new(),
+            shutdown: CancellationToken::new(),
+        }))
+    }
+
+    pub fn enqueue(&self, job: TransferJob) -> Result<u64> {
+        let id = self.update(|queue| {
+            queue.next_id += 1;
+            let id = queue.next_id;
+            queue.jobs.insert(
+                id,
+                JobRecord {
+                    id,
+                    job,
+                    state: JobState::Queued,
+                    attempts: 0,
+                    error: None,
+                    upload: None,
+                },
+            );
+            Ok(id)
+        })?;
+        self.wakeup.notify_one();
+        Ok(id)
+    }
+
+    // reported for the jobs started from now on, the events carry the file name
+    pub fn set_progress(&self, progress: Option<Arc<dyn Progress>>) {
+        *self.progress.lock().unwrap() = progress;
+    }
+
+    pub fn jobs(&self) -> Vec<JobRecord> {
+        self.queue.lock().unwrap().jobs.values().cloned().collect()
+    }
+
+    pub fn job(&self, id: u64) -> Option<JobRecord> {
+        self.queue.lock().unwrap().jobs.get(&id).cloned()
+    }
+
+    pub fn pause(&self, id: u64) -> Result<()> {
+        self.update(|queue| {
+            let record = Self::record(queue, id)?;
+            match record.state {
+                JobState::Queued | JobState::Running => record.state = JobState::Paused,
+                state => return Err(anyhow!("job {} is {:?} and can't be paused", id, state)),
+            }
+            Ok(())
+        })?;
+        self.interrupt(id);
+        Ok(())
+    }
+
+    pub fn resume(&self, id: u64) -> Result<()> {
+        self.requeue(id, JobState::Paused)
+    }
+
+    // queues a failed job again with a fresh set of attempts
+    pub fn retry(&self, id: u64) -> Result<()> {
+        self.requeue(id, JobState::Failed)
+    }
+
+    // stops the job if it's running and drops it from the queue
+    pub fn cancel(&self, id: u64) -> Result<()> {
+        self.update(|queue| {
+            queue
+                .jobs
+                .remove(&id)
+                .ok_or_else(|| anyhow!("job {} not found", id))?;
+            Ok(())
+        })?;
+        self.interrupt(id);
+        Ok(())
+    }
+
+    // removes the finished jobs from the queue
+    pub fn clear_done(&self) -> Result<()> {
+        self.update(|queue| {
+            queue
+                .jobs
+                .retain(|_, record| record.state != JobState::Done);
+            Ok(())
+        })
+    }
+
+    // makes run() return after interrupting the running jobs, which stay queued
+    pub fn shutdown(&self) {
+        self.shutdown.cancel();
+    }
+
+    // drives the queue until shutdown() is called
+    pub async fn run(self: Arc<Self>) -> Result<()> {
+        let mut tasks = JoinSet::new();
+        loop {
+            while tasks.len() < self.max_concurrency {
+                let Some((id, job, token)) = self.start_next() else {
+                    break;
+                };
+                let manager = Arc::clone(&self);
+                tasks.spawn(async move {
+                    let result = tokio::select! {
+                        _ = token.cancelled() => None,
+                        result = manager.execute(id, &job) => Some(result),
+                    };
+                    manager.running.lock().unwrap().remove(&id);
+                    if let Err(err) = manager.finish(id, result) {
+                        manager.save_failed(id, err);
+                    }
+                });
+            }
+            tokio::select! {
+                _ = self.shutdown.cancelled() => break,
+                Some(joined) = tasks.join_next(), if !tasks.is_empty() => joined?,
+                _ = self.wakeup.notified() => {}
+            }
+        }
+        for token in self.running.lock().unwrap().values() {
+            token.cancel();
+        }
+        while let Some(joined) = tasks.join_next().await {
+            joined?;
+        }
+        Ok(())
+    }
+
+    async fn execute(self: &Arc<Self>, id: u64, job: &TransferJob) -> Result<()> {
+        let progress = self.progress.lock().unwrap().clone();
+        match job {
+            TransferJob::Upload {
+                drive_id,
+                parent_id,
+                file_path,
+                if_name_exists,
+                bandwidth_limit,
+            } => {
+                let manager = Arc::clone(self);
+                let options = UploadOptions {
+                    if_name_exists: *if_name_exists,
+                    progress,
+                    bandwidth_limit: bandwidth_limit.map(|limit| BandwidthLimit::new(Some(limit))),
+                    upload_started: Some(Arc::new(
+                        move |file_id: &FileId, upload_id: &UploadId| {
+                            let session = UploadSession {
+                                file_id: file_id.clone(),
+                                upload_id: upload_id.clone(),
+                            };
+                            manager.set_upload(id, Some(session));
+                        },
+                    )),
+                    ..Default::default()
+                };
+                if let Some(session) = self.job(id).and_then(|record| record.upload) {
+                    let result = self
+                        .api
+                        .resume_upload(
+                            drive_id,
+                            &session.file_id,
+                            &session.upload_id,
+                            file_path,
+                            Some(&options),
+                        )
+                        .await;
+                    match result {
+                        Ok(_) => return Ok(()),
+                        // the server wasn't reached, the next attempt tries again
+                        Err(err) if err.downcast_ref::<reqwest::Error>().is_some() => {
+                            return Err(err)
+                        }
+                        // expired, or the local file doesn't match it anymore
+                        Err(_) => self.set_upload(id, None),
+                    }
+                }
+                self.api
+                    .upload_file(drive_id, parent_id, file_path, Some(&options))
+                    .await
+                    .map(|_| ())
+            }
+            TransferJob::Download {
+                drive_id,
+                file_id,
+                target_dir,
+                bandwidth_limit,
+            } => {
+                let options = DownloadOptions {
+                    progress,
+                    bandwidth_limit: bandwidth_limit.map(|limit| BandwidthLimit::new(Some(limit))),
+                };
+                self.api
+                    .download_file(drive_id, file_id, target_dir, None, Some(&options))
+                    .await
+            }
+        }
+    }
+
+    // the job is started even when the queue can't be saved, it carries the
+    // error instead
+    fn start_next(&self) -> Option<(u64, TransferJob, CancellationToken)> {
+        let mut queue = self.queue.lock().unwrap();
+        let record = queue
+            .jobs
+            .values_mut()
+            .find(|record| record.state == JobState::Queued)?;
+        record.state = JobState::Running;
+        // registered under the queue lock so a pause right after can't miss it
+        let token = CancellationToken::new();
+        self.running
+            .lock()
+            .unwrap()
+            .insert(record.id, token.clone());
+        let (id, job) = (record.id, record.job.clone());
+        if let Err(err) = self.save(&queue) {
+            Self::set_save_error(&mut queue, id, err);
+        }
+        Some((id, job, token))
+    }
+
+    // None when the job was interrupted by pause, cancel or shutdown
+    fn finish(&self, id: u64, result: Option<Result<()>>) -> Result<()> {
+        let max_attempts = self.max_attempts;
+        self.update(|queue| {
+            let Some(record) = queue.jobs.get_mut(&id) else {
+                return Ok(());
+            };
+            if record.state != JobState::Running {
+                return Ok(());
+            }
+            match result {
+                None => record.state = JobState::Queued,
+                Some(Ok(())) => {
+                    record.state = JobState::Done;
+                    record.error = None;
+                    record.upload = None;
+                }
+                Some(Err(err)) => {
+                    record.attempts += 1;
+                    record.error = Some(format!("{:#}", err));
+                    record.state = if record.attempts < max_attempts {
+                        JobState::Queued
+                    } else {
+                        JobState::Failed
+                    };
+                }
+            }
+            Ok(())
+        })?;
+        self.wakeup.notify_one();
+        Ok(())
+    }
+
+    fn requeue(&self, id: u64, from: JobState) -> Result<()> {
+        self.update(|queue| {
+            let record = Self::record(queue, id)?;
+            if record.state != from {
+                return Err(anyhow!("job {} is {:?}, not {:?}", id, record.state, from));
+            }
+            record.state = JobState::Queued;
+            record.attempts = 0;
+            Ok(())
+        })?;
+        self.wakeup.notify_one();
+        Ok(())
+    }
+
+    fn set_upload(&self, id: u64, upload: Option<UploadSession>) {
+        let result = self.update(|queue| {
+            if let Some(record) = queue.jobs.get_mut(&id) {
+                record.upload = upload;
+            }
+            Ok(())
+        });
+        if let Err(err) = result {
+            self.save_failed(id, err);
+        }
+    }
+
+    // a write which failed is kept on the job, so it doesn't stop the others
+    fn save_failed(&self, id: u64, err: anyhow::Error) {
+        Self::set_save_error(&mut self.queue.lock().unwrap(), id, err);
+    }
+
+    fn set_save_error(queue: &mut QueueFile, id: u64, err: anyhow::Error) {
+        if let Some(record) = queue.jobs.get_mut(&id) {
+            record.error = Some(format!("the queue couldn't be saved: {:#}", err));
+        }
+    }
+
+    fn interrupt(&self, id: u64) {
+        if let Some(token) = self.running.lock().unwrap().get(&id) {
+            token.cancel();
+        }
+    }
+
+    fn record(queue: &mut QueueFile, id: u64) -> Result<&mut JobRecord> {
+        queue
+            .jobs
+            .get_mut(&id)
+            .ok_or_else(|| anyhow!("job {} not found", id))
+    }
+
+    // applies the change and writes the queue back before anyone else sees it
+    fn update<T>(&self, f: impl FnOnce(&mut QueueFile) -> Result<T>) -> Result<T> {
+        let mut queue = self.queue.lock().unwrap();
+        let value = f(&mut queue)?;
+        self.save(&queue)?;
+        Ok(value)
+    }
+
+    fn save(&self, queue: &QueueFile) -> Result<()> {
+        if let Some(dir) = self.state_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp_path = self.state_path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(queue)?)?;
+        fs::rename(&tmp_path, &self.state_path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join("adrive-api-rs").join(format!(
+            "{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn upload(file_path: &str) -> TransferJob {
+        TransferJob::Upload {
+            drive_id: "drive".into(),
+            parent_id: FileId::ROOT,
+            file_path: file_path.to_string(),
+            if_name_exists: IfNameExists::default(),
+            bandwidth_limit: None,
+        }
+    }
+
+    #[test]
+    fn queue_survives_restart() {
+        let path = state_path("restart");
+        let api = Arc::new(ADriveAPI::new());
+        let manager = TransferManager::open(Arc::clone(&api), &path, 2, 3).unwrap();
+        let first = manager.enqueue(upload("a")).unwrap();
+        let second = manager.enqueue(upload("b")).unwrap();
+        assert_eq!(manager.start_next().unwrap().0, first);
+        manager.pause(second).unwrap();
+        let paused = manager.job(second).unwrap();
+        drop(manager);
+
+        let manager = TransferManager::open(api, &path, 2, 3).unwrap();
+        let states: Vec<_> = manager.jobs().iter().map(|r| (r.id, r.state)).collect();
+        assert_eq!(
+            states,
+            vec![(first, JobState::Queued), (second, JobState::Paused)]
+        );
+        assert_eq!(manager.job(second).unwrap(), paused);
+        assert_eq!(paused.job, upload("b"));
+        assert!(manager.enqueue(upload("c")).unwrap() > second);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn failed_jobs_are_retried_until_attempts_run_out() {
+        let path = state_path("retry");
+        let manager = TransferManager::open(Arc::new(ADriveAPI::new()), &path, 1, 2).unwrap();
+        let id = manager.enqueue(upload("a")).unwrap();
+        for state in [JobState::Queued, JobState::Failed] {
+            manager.start_next().unwrap();
+            manager.finish(id, Some(Err(anyhow!("boom")))).unwrap();
+            assert_eq!(manager.job(id).unwrap().state, state);
+        }
+        assert_eq!(manager.job(id).unwrap().error.as_deref(), Some("boom"));
+        assert!(manager.resume(id).is_err());
+
+        manager.retry(id).unwrap();
+        manager.start_next().unwrap();
+        manager.finish(id, Some(Ok(()))).unwrap();
+        assert_eq!(manager.job(id).unwrap().state, JobState::Done);
+        manager.clear_done().unwrap();
+        assert!(manager.jobs().is_empty());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn save_errors_are_kept_on_the_job() {
+        let path = state_path("save");
+        let manager = TransferManager::open(Arc::new(ADriveAPI::new()), &path, 1, 1).unwrap();
+        let id = manager.enqueue(upload("a")).unwrap();
+        // the queue is written through the tmp file, which can't be a folder
+        let tmp_path = path.with_extension("tmp");
+        fs::create_dir_all(&tmp_path).unwrap();
+        assert_eq!(manager.start_next().unwrap().0, id);
+        let record = manager.job(id).unwrap();
+        assert_eq!(record.state, JobState::Running);
+        assert!(record
+            .error
+            .unwrap()
+            .starts_with("the queue couldn't be saved"));
+
+        let err = manager.finish(id, Some(Ok(()))).unwrap_err();
+        manager.save_failed(id, err);
+        let record = manager.job(id).unwrap();
+        assert_eq!(record.state, JobState::Done);
+        assert!(record.error.is_some());
+        fs::remove_dir(&tmp_path).unwrap();
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn interrupted_jobs_keep_their_state() {
+        let path = state_path("interrupt");
+        let manager = TransferManager::open(Arc::new(ADriveAPI::new()), &path, 1, 1).unwrap();
+        let paused = manager.enqueue(upload("a")).unwrap();
+        let cancelled = manager.enqueue(upload("b")).unwrap();
+        manager.start_next().unwrap();
+        manager.pause(paused).unwrap();
+        manager.finish(paused, None).unwrap();
+        assert_eq!(manager.job(paused).unwrap().state, JobState::Paused);
+
+        manager.start_next().unwrap();
+        manager.cancel(cancelled).unwrap();
+        manager.finish(cancelled, None).unwrap();
+        assert!(manager.job(cancelled).is_none());
+
+        manager.resume(paused).unwrap();
+        assert_eq!(manager.job(paused).unwrap().state, JobState::Queued);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn upload_sessions_are_kept_until_the_job_is_done() {
+        let path = state_path("session");
+        let api = Arc::new(ADriveAPI::new());
+        let manager = TransferManager::open(Arc::clone(&api), &path, 1, 3).unwrap();
+        let id = manager.enqueue(upload("a")).unwrap();
+        manager.start_next().unwrap();
+        let session = UploadSession {
+            file_id: "f1".into(),
+            upload_id: "u1".into(),
+        };
+        manager.set_upload(id, Some(session.clone()));
+        manager.finish(id, Some(Err(anyhow!("boom")))).unwrap();
+        drop(manager);
+
+        let manager = TransferManager::open(api, &path, 1, 3).unwrap();
+        assert_eq!(manager.job(id).unwrap().upload, Some(session));
+        manager.start_next().unwrap();
+        manager.finish(id, Some(Ok(()))).unwrap();
+        assert_eq!(manager.job(id).unwrap().upload, None);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn upload_jobs_default_to_keeping_existing_files() {
+        let job: TransferJob = serde_json::from_str(
+            r#"{"kind": "upload", "drive_id": "drive", "parent_id": "root", "file_path": "a"}"#,
+        )
+        .unwrap();
+        assert_eq!(job, upload("a"));
+        assert!(matches!(
+            job,
+            TransferJob::Upload {
+                if_name_exists: IfNameExists::AutoRename,
+                bandwidth_limit: None,
+                ..
+            }
+        ));
+
+        let job = TransferJob::Upload {
+            drive_id: "d".into(),
+            parent_id: FileId::ROOT,
+            file_path: "a".to_string(),
+            if_name_exists: IfNameExists::Replace,
+            bandwidth_limit: Some(1024),
+        };
+        let value = serde_json::to_value(&job).unwrap();
+        assert_eq!(value["if_name_exists"], "replace");
+        assert_eq!(serde_json::from_value::<TransferJob>(value).unwrap(), job);
+    }
+}
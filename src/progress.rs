@@ -0,0 +1,127 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Hashing,
+    RapidCheck,
+    Uploading,
+    Completing,
+    Downloading,
+    Done,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProgressEvent {
+    pub file_name: String,
+    pub phase: Phase,
+    // unknown for streams of unknown length
+    pub total_bytes: Option<u64>,
+    pub transferred_bytes: u64,
+    // the part or chunk just transferred, counting from 1
+    pub part: Option<u32>,
+    // bytes per second since the transfer started
+    pub throughput: f64,
+}
+
+pub trait Progress: Send + Sync {
+    fn on_progress(&self, event: &ProgressEvent);
+}
+
+impl<F: Fn(&ProgressEvent) + Send + Sync> Progress for F {
+    fn on_progress(&self, event: &ProgressEvent) {
+        self(event)
+    }
+}
+
+impl Progress for tokio::sync::mpsc::UnboundedSender<ProgressEvent> {
+    fn on_progress(&self, event: &ProgressEvent) {
+        let _ = self.send(event.clone());
+    }
+}
+
+pub(crate) struct ProgressTracker {
+    progress: Option<Arc<dyn Progress>>,
+    file_name: String,
+    total_bytes: Option<u64>,
+    transferred_bytes: AtomicU64,
+    started: Instant,
+}
+
+impl ProgressTracker {
+    pub(crate) fn new(
+        progress: Option<Arc<dyn Progress>>,
+        file_name: &str,
+        total_bytes: Option<u64>,
+    ) -> Self {
+        Self {
+            progress,
+            file_name: file_name.to_string(),
+            total_bytes,
+            transferred_bytes: AtomicU64::new(0),
+            started: Instant::now(),
+        }
+    }
+
+    pub(crate) fn phase(&self, phase: Phase) {
+        let transferred_bytes = self.transferred_bytes.load(Ordering::Relaxed);
+        self.emit(phase, transferred_bytes, None);
+    }
+
+    pub(crate) fn advance(&self, phase: Phase, bytes: u64, part: u32) {
+        let transferred_bytes = self.transferred_bytes.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        self.emit(phase, transferred_bytes, Some(part));
+    }
+
+    fn emit(&self, phase: Phase, transferred_bytes: u64, part: Option<u32>) {
+        let Some(progress) = self.progress.as_ref() else {
+            return;
+        };
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let throughput = if elapsed > 0.0 {
+            transferred_bytes as f64 / elapsed
+        } else {
+            0.0
+        };
+        progress.on_progress(&ProgressEvent {
+            file_name: self.file_name.clone(),
+            phase,
+            total_bytes: self.total_bytes,
+            transferred_bytes,
+            part,
+            throughput,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracker_accumulates_parts() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let tracker = ProgressTracker::new(Some(Arc::new(tx)), "a.bin", Some(10));
+        tracker.phase(Phase::Hashing);
+        tracker.advance(Phase::Uploading, 4, 1);
+        tracker.advance(Phase::Uploading, 6, 2);
+        tracker.phase(Phase::Done);
+
+        let events: Vec<ProgressEvent> = std::iter::from_fn(|| rx.try_recv().ok()).collect();
+        let seen: Vec<_> = events
+            .iter()
+            .map(|e| (e.phase, e.transferred_bytes, e.part))
+            .collect();
+        assert_eq!(
+            seen,
+            vec![
+                (Phase::Hashing, 0, None),
+                (Phase::Uploading, 4, Some(1)),
+                (Phase::Uploading, 10, Some(2)),
+                (Phase::Done, 10, None),
+            ]
+        );
+        assert!(events.iter().all(|e| e.total_bytes == Some(10)));
+    }
+}
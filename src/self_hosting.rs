@@ -43,9 +43,7 @@ async fn refresh_token(payload: Json<GetAccessTokenRequest3>) -> Json<GetAccessT
     Json(resp)
 }
 
-async fn ping() {
-    ()
-}
+async fn ping() {}
 
 pub fn app() -> Router {
     Router::new()
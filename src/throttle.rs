@@ -0,0 +1,137 @@
+use crate::Result;
+use bytes::{Bytes, BytesMut};
+use futures_util::{stream, Stream, StreamExt};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+// bodies are throttled in slices of this size so a cap also smooths out a part
+const SLICE_SIZE: usize = 64 * 1024;
+
+// a bandwidth cap in bytes per second, shared by every clone so it can be
+// changed while transfers are running
+#[derive(Debug, Clone, Default)]
+pub struct BandwidthLimit {
+    inner: Arc<LimitState>,
+}
+
+#[derive(Debug, Default)]
+struct LimitState {
+    // 0 means unlimited
+    bytes_per_sec: AtomicU64,
+    // the instant the bytes granted so far have been paid off
+    paid_until: Mutex<Option<Instant>>,
+}
+
+impl BandwidthLimit {
+    pub fn new(bytes_per_sec: Option<u64>) -> Self {
+        let limit = Self::default();
+        limit.set(bytes_per_sec);
+        limit
+    }
+
+    pub fn set(&self, bytes_per_sec: Option<u64>) {
+        self.inner
+            .bytes_per_sec
+            .store(bytes_per_sec.unwrap_or(0), Ordering::Relaxed);
+        // don't let a backlog booked under the old rate hold up the new one
+        *self.inner.paid_until.lock().unwrap() = None;
+    }
+
+    pub fn get(&self) -> Option<u64> {
+        match self.inner.bytes_per_sec.load(Ordering::Relaxed) {
+            0 => None,
+            bytes_per_sec => Some(bytes_per_sec),
+        }
+    }
+
+    // books the bytes and returns when they may be sent
+    fn reserve(&self, bytes: u64, now: Instant) -> Instant {
+        let Some(bytes_per_sec) = self.get() else {
+            return now;
+        };
+        let mut paid_until = self.inner.paid_until.lock().unwrap();
+        let start = paid_until.map_or(now, |t| t.max(now));
+        let ready = start + Duration::from_secs_f64(bytes as f64 / bytes_per_sec as f64);
+        *paid_until = Some(ready);
+        ready
+    }
+}
+
+// waits until every limit allows the bytes to go through
+pub(crate) async fn acquire(limits: &[BandwidthLimit], bytes: u64) {
+    let now = Instant::now();
+    let ready = limits
+        .iter()
+        .map(|limit| limit.reserve(bytes, now))
+        .max()
+        .unwrap_or(now);
+    if ready > now {
+        tokio::time::sleep_until(ready).await;
+    }
+}
+
+// an upload body which hands out its data no faster than the limits allow
+pub(crate) fn throttled_body(
+    data: Bytes,
+    limits: Vec<BandwidthLimit>,
+) -> impl Stream<Item = std::io::Result<Bytes>> + Send + Sync + 'static {
+    stream::unfold((data, limits), |(mut data, limits)| async move {
+        if data.is_empty() {
+            return None;
+        }
+        let slice = data.split_to(SLICE_SIZE.min(data.len()));
+        acquire(&limits, slice.len() as u64).await;
+        Some((Ok(slice), (data, limits)))
+    })
+}
+
+// reads a download response, pausing between chunks to stay under the limits
+pub(crate) async fn read_body(resp: reqwest::Response, limits: &[BandwidthLimit]) -> Result<Bytes> {
+    let mut body = BytesMut::with_capacity(resp.content_length().unwrap_or(0) as usize);
+    let mut chunks = resp.bytes_stream();
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk?;
+        acquire(limits, chunk.len() as u64).await;
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body.freeze())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn unlimited_does_not_wait() {
+        let started = Instant::now();
+        acquire(&[BandwidthLimit::default()], 10 * 1024 * 1024).await;
+        assert_eq!(started.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn slowest_limit_wins() {
+        let fast = BandwidthLimit::new(Some(1000));
+        let slow = BandwidthLimit::new(Some(100));
+        let started = Instant::now();
+        for _ in 0..5 {
+            acquire(&[fast.clone(), slow.clone()], 100).await;
+        }
+        assert_eq!(started.elapsed(), Duration::from_secs(5));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn limit_can_be_raised_at_runtime() {
+        let limits = [BandwidthLimit::new(Some(100))];
+        let started = Instant::now();
+        acquire(&limits, 100).await;
+        assert_eq!(started.elapsed(), Duration::from_secs(1));
+        limits[0].set(Some(1000));
+        acquire(&limits, 1000).await;
+        assert_eq!(started.elapsed(), Duration::from_secs(2));
+        limits[0].set(None);
+        acquire(&limits, 1000).await;
+        assert_eq!(started.elapsed(), Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,119 @@
+use crate::data::{FileEntry, FileId, IfNameExists, UploadId};
+use crate::progress::Progress;
+use crate::throttle::BandwidthLimit;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+#[derive(Debug, Default)]
+pub struct DownloadDirSummary {
+    pub downloaded: Vec<PathBuf>,
+    // local copy already matches size and content_hash
+    pub skipped: Vec<PathBuf>,
+    pub failed: Vec<(PathBuf, anyhow::Error)>,
+}
+
+#[derive(Debug, Default)]
+pub struct UploadDirSummary {
+    pub folder_id: FileId,
+    pub uploaded: Vec<PathBuf>,
+    pub rapid_uploaded: Vec<PathBuf>,
+    // matched an ignore pattern, isn't a regular file (symlinks included),
+    // lives in a folder which failed to be created or already exists under
+    // IfNameExists::Refuse
+    pub skipped: Vec<PathBuf>,
+    // files which failed to upload and folders which failed to be created
+    pub failed: Vec<(PathBuf, anyhow::Error)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadKind {
+    // the server already had the content, no bytes were sent
+    Rapid,
+    // some parts were uploaded by an earlier attempt
+    Resumed,
+    Transferred,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UploadOutcome {
+    pub file: FileEntry,
+    pub kind: UploadKind,
+    pub bytes_sent: u64,
+}
+
+pub type UploadStarted = Arc<dyn Fn(&FileId, &UploadId) + Send + Sync>;
+
+#[derive(Clone)]
+pub struct UploadOptions {
+    // chosen from the file size when None
+    pub part_size: Option<u64>,
+    pub if_name_exists: IfNameExists,
+    // taken from the source file when None and preserve_timestamps is set
+    pub local_created_at: Option<DateTime<Utc>>,
+    pub local_modified_at: Option<DateTime<Utc>>,
+    pub preserve_timestamps: bool,
+    // fail before sending anything when the drive can't hold the upload
+    pub check_space: bool,
+    // headroom which has to stay free after the upload
+    pub reserved_space: u64,
+    pub progress: Option<Arc<dyn Progress>>,
+    // applied on top of the client wide limit
+    pub bandwidth_limit: Option<BandwidthLimit>,
+    // called once a multipart upload is created with the ids resume_upload
+    // needs to continue it
+    pub upload_started: Option<UploadStarted>,
+}
+
+impl fmt::Debug for UploadOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("UploadOptions")
+            .field("part_size", &self.part_size)
+            .field("if_name_exists", &self.if_name_exists)
+            .field("local_created_at", &self.local_created_at)
+            .field("local_modified_at", &self.local_modified_at)
+            .field("preserve_timestamps", &self.preserve_timestamps)
+            .field("check_space", &self.check_space)
+            .field("reserved_space", &self.reserved_space)
+            .field("progress", &self.progress.is_some())
+            .field("bandwidth_limit", &self.bandwidth_limit)
+            .field("upload_started", &self.upload_started.is_some())
+            .finish()
+    }
+}
+
+impl Default for UploadOptions {
+    fn default() -> Self {
+        Self {
+            part_size: None,
+            if_name_exists: IfNameExists::default(),
+            local_created_at: None,
+            local_modified_at: None,
+            preserve_timestamps: true,
+            check_space: true,
+            reserved_space: 0,
+            progress: None,
+            bandwidth_limit: None,
+            upload_started: None,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct DownloadOptions {
+    pub progress: Option<Arc<dyn Progress>>,
+    // applied on top of the client wide limit
+    pub bandwidth_limit: Option<BandwidthLimit>,
+}
+
+impl fmt::Debug for DownloadOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DownloadOptions")
+            .field("progress", &self.progress.is_some())
+            .field("bandwidth_limit", &self.bandwidth_limit)
+            .finish()
+    }
+}
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use std::{cmp, fs};
 
 use base64::prelude::*;
+use chrono::{DateTime, SecondsFormat, Utc};
 use sha1_smol::Sha1;
 
 pub(crate) fn ensure_dirs(dir: &str) -> crate::Result<PathBuf> {
@@ -11,10 +12,15 @@ pub(crate) fn ensure_dirs(dir: &str) -> crate::Result<PathBuf> {
     Ok(path)
 }
 
-pub(crate) fn get_proof_code(file: &mut fs::File, size: u64, token: &str) -> crate::Result<String> {
-    file.seek(SeekFrom::Start(0))?;
+// the api expects local times like 2019-08-20T06:51:27.292Z
+pub(crate) fn format_time(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+// the bytes [start, end) the proof code is made of, derived from the access token
+pub(crate) fn get_proof_range(size: u64, token: &str) -> crate::Result<(u64, u64)> {
     if size == 0 {
-        return Ok(String::from(""));
+        return Ok((0, 0));
     }
     let digest = md5::compute(token);
     let hex = format!("{:x}", digest);
@@ -22,15 +28,32 @@ pub(crate) fn get_proof_code(file: &mut fs::File, size: u64, token: &str) -> cra
 
     let start = uint % size;
     let end = cmp::min(start + 8, size);
+    Ok((start, end))
+}
+
+pub(crate) fn encode_proof_code(buf: &[u8]) -> String {
+    BASE64_STANDARD.encode(buf)
+}
+
+pub(crate) fn get_proof_code<R: Read + Seek>(
+    file: &mut R,
+    size: u64,
+    token: &str,
+) -> crate::Result<String> {
+    file.seek(SeekFrom::Start(0))?;
+    if size == 0 {
+        return Ok(String::from(""));
+    }
+    let (start, end) = get_proof_range(size, token)?;
 
     let mut buf = vec![0u8; (end - start) as usize];
     file.seek(SeekFrom::Start(start))?;
 
     file.read_exact(&mut buf)?;
-    Ok(BASE64_STANDARD.encode(&buf))
+    Ok(encode_proof_code(&buf))
 }
 
-pub(crate) fn get_content_hash(file: &mut fs::File) -> crate::Result<String> {
+pub(crate) fn get_content_hash<R: Read + Seek>(file: &mut R) -> crate::Result<String> {
     file.seek(SeekFrom::Start(0))?;
     let mut hasher = Sha1::new();
     let mut buffer = vec![0u8; 10 * 1024];
@@ -45,7 +68,7 @@ pub(crate) fn get_content_hash(file: &mut fs::File) -> crate::Result<String> {
     Ok(hasher.hexdigest().to_uppercase())
 }
 
-pub(crate) fn get_pre_hash(file: &mut fs::File) -> crate::Result<String> {
+pub(crate) fn get_pre_hash<R: Read + Seek>(file: &mut R) -> crate::Result<String> {
     // TODO 1024?
     file.seek(SeekFrom::Start(0))?;
     let mut buffer = vec![0u8; 1024];
@@ -55,3 +78,74 @@ pub(crate) fn get_pre_hash(file: &mut fs::File) -> crate::Result<String> {
     hasher.update(data);
     Ok(hasher.hexdigest().to_uppercase())
 }
+
+// shell style wildcard, `*` matches any run of characters and `?` a single one
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        format_time, get_content_hash, get_pre_hash, get_proof_code, get_proof_range,
+        wildcard_match,
+    };
+    use chrono::{TimeZone, Utc};
+    use std::io::Cursor;
+
+    #[test]
+    fn local_time_format() {
+        let time = Utc.timestamp_millis_opt(1566283887292).unwrap();
+        assert_eq!(format_time(&time), "2019-08-20T06:51:27.292Z");
+    }
+
+    #[test]
+    fn hashes_of_empty_file() {
+        let mut file = Cursor::new(Vec::new());
+        assert_eq!(get_proof_code(&mut file, 0, "token").unwrap(), "");
+        let sha1 = "DA39A3EE5E6B4B0D3255BFEF95601890AFD80709";
+        assert_eq!(get_content_hash(&mut file).unwrap(), sha1);
+        assert_eq!(get_pre_hash(&mut file).unwrap(), sha1);
+    }
+
+    #[test]
+    fn proof_code_of_tiny_file() {
+        let mut file = Cursor::new(b"abc".to_vec());
+        assert_eq!(get_proof_range(3, "token").unwrap(), (2, 3));
+        assert_eq!(get_proof_code(&mut file, 3, "token").unwrap(), "Yw==");
+    }
+
+    #[test]
+    fn wildcard_patterns() {
+        assert!(wildcard_match("*.tmp", "a.tmp"));
+        assert!(wildcard_match("*.tmp", ".tmp"));
+        assert!(!wildcard_match("*.tmp", "a.tmp.bak"));
+        assert!(wildcard_match(".git", ".git"));
+        assert!(!wildcard_match(".git", ".github"));
+        assert!(wildcard_match("?.log", "a.log"));
+        assert!(!wildcard_match("?.log", "ab.log"));
+        assert!(!wildcard_match("?.log", ".log"));
+        assert!(wildcard_match("build*", "build"));
+        assert!(wildcard_match("build*", "build-cache"));
+        assert!(!wildcard_match("build*", "rebuild"));
+    }
+}
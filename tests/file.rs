@@ -1,7 +1,9 @@
 mod common;
 
-use adrive_api_rs::{ADriveAPI, Result};
+use adrive_api_rs::{ADriveAPI, Bytes, FileId, IfNameExists, Result, ThumbnailOptions};
 use chrono::Utc;
+use std::path::Path;
+use std::time::Duration;
 
 #[tokio::test]
 #[ignore]
@@ -11,7 +13,7 @@ async fn test_file() -> Result<()> {
 
     let drive_id = adrive_api.get_backup_drive_id().await?;
 
-    let parent_id = "root";
+    let parent_id = &FileId::ROOT;
     let resp = adrive_api.list_files(&drive_id, parent_id).await?;
     println!("{:#?}", resp);
 
@@ -22,7 +24,7 @@ async fn test_file() -> Result<()> {
     let resp = adrive_api.list_starred_files(&drive_id).await?;
     println!("{:#?}", resp);
 
-    let file_id = "62e89bd3b9d6ab9196c949a8b6a0f63a4dc22857";
+    let file_id = &FileId::from("62e89bd3b9d6ab9196c949a8b6a0f63a4dc22857");
     let resp = adrive_api.get_file_by_id(&drive_id, file_id).await?;
     println!("{:#?}", resp);
 
@@ -31,75 +33,138 @@ async fn test_file() -> Result<()> {
     println!("{:#?}", resp);
 
     let file_ids = [
-        "64be5da63e2df2c57312431bb0ecd17c0960eb34",
-        "62e89bd3b9d6ab9196c949a8b6a0f63a4dc22857",
+        FileId::from("64be5da63e2df2c57312431bb0ecd17c0960eb34"),
+        FileId::from("62e89bd3b9d6ab9196c949a8b6a0f63a4dc22857"),
     ];
     let resp = adrive_api.batch_get_files(&drive_id, &file_ids).await?;
     println!("{:#?}", resp);
 
-    let file_id = "62e89bd3b9d6ab9196c949a8b6a0f63a4dc22857";
+    let file_id = &FileId::from("62e89bd3b9d6ab9196c949a8b6a0f63a4dc22857");
     let resp = adrive_api.get_download_url(&drive_id, file_id).await?;
     println!("{:#?}", resp);
 
-    let file_id = "62e89bd3b9d6ab9196c949a8b6a0f63a4dc22857";
+    let resp = adrive_api
+        .get_video_preview_play_info(&drive_id, file_id, Some(3600))
+        .await?;
+    println!("{:#?}", resp.best());
+    adrive_api
+        .update_play_cursor(&drive_id, file_id, Duration::from_secs(42), None)
+        .await?;
+    let resp = adrive_api.get_file_by_id(&drive_id, file_id).await?;
+    assert_eq!(resp.play_cursor, Some(Duration::from_secs(42)));
+
+    let thumbnail = ThumbnailOptions {
+        width: Some(480),
+        video_time: Some(Duration::from_secs(10)),
+    };
+    let cache_dir = Path::new("./tmp/thumbnails");
+    let bytes = adrive_api
+        .download_thumbnail(&resp, &thumbnail, Some(cache_dir))
+        .await?;
+    let cached = adrive_api
+        .download_thumbnail(&resp, &thumbnail, Some(cache_dir))
+        .await?;
+    assert_eq!(bytes, cached);
+
+    let file_id = &FileId::from("62e89bd3b9d6ab9196c949a8b6a0f63a4dc22857");
     let target_dir = "./tmp";
     adrive_api
-        .concurrent_download_file(&drive_id, file_id, target_dir, None)
+        .concurrent_download_file(&drive_id, file_id, target_dir, None, None)
+        .await?;
+    println!("{:#?}", resp);
+
+    let folder_id = &FileId::from("65ab71583bc7891eebd6491b8c1d67b0c450c306");
+    let resp = adrive_api
+        .download_dir(&drive_id, folder_id, "./tmp/dir", None)
         .await?;
     println!("{:#?}", resp);
 
     let dir_name = "test";
     let resp = adrive_api
-        .create_folder(&drive_id, "root", dir_name)
+        .create_folder(&drive_id, &FileId::ROOT, dir_name, None)
         .await?;
     println!("{:#?}", resp);
     adrive_api.delete_file(&drive_id, &resp).await?;
     println!("{:#?}", resp);
 
-    let parent_id: &str = "65abd292f867ee78856b4c1ba5db30af4b9213a6";
+    let parent_id = &FileId::from("65abd292f867ee78856b4c1ba5db30af4b9213a6");
     let file_path = "./tmp/test.file2";
     adrive_api
-        .upload_file(&drive_id, parent_id, file_path)
+        .upload_file(&drive_id, parent_id, file_path, None)
+        .await?;
+    println!("{:#?}", resp);
+
+    let resp = adrive_api
+        .upload_bytes(&drive_id, parent_id, "report.json", Bytes::from("{}"), None)
         .await?;
     println!("{:#?}", resp);
 
-    let file_id = "62e89bd3b9d6ab9196c949a8b6a0f63a4dc22857";
+    let local_dir = "./tmp/dir";
+    let resp = adrive_api
+        .upload_dir(&drive_id, parent_id, local_dir, &["*.tmp", ".git"], None)
+        .await?;
+    println!("{:#?}", resp);
+
+    let file_id = &FileId::from("62e89bd3b9d6ab9196c949a8b6a0f63a4dc22857");
     let resp = adrive_api.star_file(&drive_id, file_id).await?;
     println!("{:#?}", resp);
     let resp = adrive_api.unstar_file(&drive_id, file_id).await?;
     println!("{:#?}", resp);
 
-    let file_id = "65ab71583bc7891eebd6491b8c1d67b0c450c306";
+    let file_id = &FileId::from("65ab71583bc7891eebd6491b8c1d67b0c450c306");
     let _resp = adrive_api
         .rename_file(
             &drive_id,
             file_id,
             &format!("abc-{}", &Utc::now().timestamp().to_string()),
+            Some(IfNameExists::Refuse),
         )
         .await?;
 
-    let file_id = "63fcd09f609ce464d23944289fd4d583f8ca100b";
-    let target_parent_id = "65ab71583bc7891eebd6491b8c1d67b0c450c306";
+    let file_id = &FileId::from("63fcd09f609ce464d23944289fd4d583f8ca100b");
+    let target_parent_id = &FileId::from("65ab71583bc7891eebd6491b8c1d67b0c450c306");
     adrive_api
-        .move_file(&drive_id, file_id, target_parent_id, None)
+        .move_file(&drive_id, file_id, target_parent_id, None, None)
         .await?;
     println!("{:#?}", resp);
     adrive_api
-        .move_file(&drive_id, file_id, "root", None)
+        .move_file(
+            &drive_id,
+            file_id,
+            &FileId::ROOT,
+            None,
+            Some(IfNameExists::Replace),
+        )
         .await?;
     println!("{:#?}", resp);
 
-    let file_id = "63fcd09f609ce464d23944289fd4d583f8ca100b";
-    let target_parent_id = "65ab71583bc7891eebd6491b8c1d67b0c450c306";
+    let file_id = &FileId::from("63fcd09f609ce464d23944289fd4d583f8ca100b");
+    let target_parent_id = &FileId::from("65ab71583bc7891eebd6491b8c1d67b0c450c306");
     let resp = adrive_api
-        .copy_file(&drive_id, file_id, target_parent_id)
+        .copy_file(&drive_id, file_id, target_parent_id, None)
         .await?;
     println!("{:#?}", resp);
     adrive_api.delete_file(&drive_id, &resp).await?;
     println!("{:#?}", resp);
 
-    let file_id = "65a3fd0ebed88b3dd64a4073be604310a2d946c7";
+    let file_id = &FileId::from("65a3fd0ebed88b3dd64a4073be604310a2d946c7");
     adrive_api.recycle_file(&drive_id, file_id).await?;
     println!("{:#?}", resp);
+    let resp = adrive_api.list_recyclebin(&drive_id).await?;
+    println!("{:#?}", resp);
+    adrive_api.restore_file(&drive_id, file_id).await?;
+
+    let file_ids = [
+        FileId::from("65a3fd0ebed88b3dd64a4073be604310a2d946c7"),
+        FileId::from("63fcd09f609ce464d23944289fd4d583f8ca100b"),
+    ];
+    let resp = adrive_api
+        .batch_copy_files(&drive_id, &file_ids, target_parent_id, None)
+        .await;
+    println!("{:#?}", resp);
+    let copied: Vec<FileId> = resp.into_iter().filter_map(|(_, r)| r.ok()).collect();
+    let resp = adrive_api.batch_recycle_files(&drive_id, &copied).await;
+    println!("{:#?}", resp);
+
     Ok(())
 }